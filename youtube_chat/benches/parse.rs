@@ -0,0 +1,56 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use youtube_chat::{
+    parser::{parse_chat_data, parse_chat_events, parse_chat_events_from_slice, ParseOptions},
+    youtube_types::GetLiveChatResponse,
+};
+
+/// A `get_live_chat` response of a busy stream: 200 actions of text messages with
+/// custom emojis and badges, superchats, stickers, memberships and deletions.
+///
+/// It is synthetic, not a recording: it was generated with the structure and field
+/// sizes of the renderers in `youtube_types`, without network access to record one.
+/// To benchmark a recorded response instead, send the request built by
+/// `request::get_live_chat_request` for a busy stream, e.g. with `curl`, trim the
+/// actions, e.g. with `jq '.continuationContents.liveChatContinuation.actions |= .[:200]'`,
+/// and pass the file in `YOUTUBE_CHAT_BENCH_FIXTURE`.
+const BUSY_STREAM: &[u8] = include_bytes!("fixtures/busy_stream.json");
+
+fn fixture() -> Vec<u8> {
+    match std::env::var_os("YOUTUBE_CHAT_BENCH_FIXTURE") {
+        Some(path) => std::fs::read(path).unwrap(),
+        None => BUSY_STREAM.to_vec(),
+    }
+}
+
+fn parse(c: &mut Criterion) {
+    let body = fixture();
+    let mut group = c.benchmark_group("get_live_chat");
+    group.throughput(Throughput::Bytes(body.len() as u64));
+
+    // How responses were parsed before: the body read as a `String`, deserialized
+    // with `from_str` and converted with `parse_chat_data`.
+    let text = String::from_utf8(body.clone()).unwrap();
+    group.bench_function("baseline_from_str", |b| {
+        b.iter(|| {
+            let response: GetLiveChatResponse = serde_json::from_str(black_box(&text)).unwrap();
+            parse_chat_data(response)
+        })
+    });
+
+    group.bench_function("typed_from_slice", |b| {
+        b.iter(|| {
+            let response: GetLiveChatResponse = serde_json::from_slice(black_box(&body)).unwrap();
+            parse_chat_events(response)
+        })
+    });
+
+    let options = ParseOptions::default();
+    group.bench_function("borrowed_actions", |b| {
+        b.iter(|| parse_chat_events_from_slice(black_box(&body), &options).unwrap())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
@@ -0,0 +1,343 @@
+//! A client and requests that run on the current thread, without an async runtime.
+
+use std::{io::Read, ops::Deref, thread, time::Duration};
+
+use crate::{
+    diagnostics::SchemaDriftReport,
+    item::ChatItem,
+    live_chat::{
+        self, InvokeOnBanner, InvokeOnChat, InvokeOnDeletion, InvokeOnEnd, InvokeOnEngagement,
+        InvokeOnError, InvokeOnEvent, InvokeOnGift, InvokeOnMembership, InvokeOnPoll, InvokeOnRaid,
+        InvokeOnRawAction, InvokeOnStart, InvokeOnSticker, InvokeOnSuperchat, InvokeOnTicker,
+        InvokeOnUnknown, LiveChatClientBuilder,
+    },
+    parser::{get_options_from_live_page, parse_chat_data, ParseOptions},
+    request::{
+        get_live_chat_request, into_owned, parse_get_live_chat, ChatEventBatch, ClientContext,
+        RequestOptions,
+    },
+    youtube_types::GetLiveChatResponse,
+};
+
+pub fn fetch_chat(options: RequestOptions) -> Result<(Vec<ChatItem>, String), anyhow::Error> {
+    let body = post_get_live_chat(&agent(None)?, options, &ClientContext::default())?;
+    let json: GetLiveChatResponse = serde_json::from_slice(&body)?;
+    Ok(parse_chat_data(json))
+}
+
+/// Blocking version of [`crate::request::fetch_chat_events`].
+pub fn fetch_chat_events(
+    options: RequestOptions,
+    parse_options: &ParseOptions,
+    drift: Option<&mut SchemaDriftReport>,
+) -> Result<ChatEventBatch, anyhow::Error> {
+    let body = post_get_live_chat(&agent(None)?, options, &ClientContext::default())?;
+    parse_get_live_chat(&body, parse_options, drift).map(into_owned)
+}
+
+fn agent(proxy: Option<&str>) -> Result<ureq::Agent, anyhow::Error> {
+    let mut builder = ureq::AgentBuilder::new();
+    if let Some(proxy) = proxy {
+        builder = builder.proxy(ureq::Proxy::new(proxy)?);
+    }
+    Ok(builder.build())
+}
+
+fn with_headers(mut request: ureq::Request, context: &ClientContext) -> ureq::Request {
+    for (name, value) in context.http_headers() {
+        request = request.set(&name, &value);
+    }
+    request
+}
+
+fn post_get_live_chat(
+    agent: &ureq::Agent,
+    options: RequestOptions,
+    context: &ClientContext,
+) -> Result<Vec<u8>, anyhow::Error> {
+    let (url, body) = get_live_chat_request(options, context);
+    let response = with_headers(agent.post(&url), context)
+        .set("Content-Type", "application/json")
+        .send_bytes(&serde_json::to_vec(&body)?)?;
+    let mut body = Vec::new();
+    response.into_reader().read_to_end(&mut body)?;
+    Ok(body)
+}
+
+pub fn fetch_live_page(url: &str) -> Result<(RequestOptions, String), anyhow::Error> {
+    fetch_live_page_through(&agent(None)?, url, &ClientContext::default())
+}
+
+fn fetch_live_page_through(
+    agent: &ureq::Agent,
+    url: &str,
+    context: &ClientContext,
+) -> Result<(RequestOptions, String), anyhow::Error> {
+    let response = with_headers(agent.get(url), context)
+        .call()?
+        .into_string()?;
+    get_options_from_live_page(response)
+}
+
+/// A [`live_chat::LiveChatClient`] whose requests block the current thread.
+/// Build it with [`LiveChatClientBuilder::build_blocking`]; the getters of the
+/// async client, e.g. `checkpoint`, are available through `Deref`.
+pub struct LiveChatClient<
+    SF,
+    ENF,
+    CF,
+    ERF,
+    SCF,
+    STF,
+    MF,
+    DF,
+    GF,
+    TF,
+    BF,
+    RDF,
+    PF,
+    EGF,
+    UF,
+    RAF,
+    EVF,
+> where
+    SF: InvokeOnStart,
+    ENF: InvokeOnEnd,
+    CF: InvokeOnChat,
+    ERF: InvokeOnError,
+    SCF: InvokeOnSuperchat,
+    STF: InvokeOnSticker,
+    MF: InvokeOnMembership,
+    DF: InvokeOnDeletion,
+    GF: InvokeOnGift,
+    TF: InvokeOnTicker,
+    BF: InvokeOnBanner,
+    RDF: InvokeOnRaid,
+    PF: InvokeOnPoll,
+    EGF: InvokeOnEngagement,
+    UF: InvokeOnUnknown,
+    RAF: InvokeOnRawAction,
+    EVF: InvokeOnEvent,
+{
+    #[allow(clippy::type_complexity)]
+    inner: live_chat::LiveChatClient<
+        SF,
+        ENF,
+        CF,
+        ERF,
+        SCF,
+        STF,
+        MF,
+        DF,
+        GF,
+        TF,
+        BF,
+        RDF,
+        PF,
+        EGF,
+        UF,
+        RAF,
+        EVF,
+    >,
+    /// The agent for the current proxy and that proxy, reused to keep connections
+    /// open until the proxy rotation moves the client to another proxy.
+    agent: Option<(ureq::Agent, Option<String>)>,
+}
+
+impl<SF, ENF, CF, ERF, SCF, STF, MF, DF, GF, TF, BF, RDF, PF, EGF, UF, RAF, EVF>
+    LiveChatClient<SF, ENF, CF, ERF, SCF, STF, MF, DF, GF, TF, BF, RDF, PF, EGF, UF, RAF, EVF>
+where
+    SF: InvokeOnStart,
+    ENF: InvokeOnEnd,
+    CF: InvokeOnChat,
+    ERF: InvokeOnError,
+    SCF: InvokeOnSuperchat,
+    STF: InvokeOnSticker,
+    MF: InvokeOnMembership,
+    DF: InvokeOnDeletion,
+    GF: InvokeOnGift,
+    TF: InvokeOnTicker,
+    BF: InvokeOnBanner,
+    RDF: InvokeOnRaid,
+    PF: InvokeOnPoll,
+    EGF: InvokeOnEngagement,
+    UF: InvokeOnUnknown,
+    RAF: InvokeOnRawAction,
+    EVF: InvokeOnEvent,
+{
+    pub fn start(&mut self) -> Result<(), anyhow::Error> {
+        let result = self.agent().and_then(|agent| {
+            fetch_live_page_through(&agent, self.inner.live_url(), self.inner.context())
+        });
+        self.inner.record_request(result.is_ok());
+        let (options, live_id) = result?;
+        self.inner.started(options, live_id);
+        Ok(())
+    }
+
+    pub fn execute(&mut self) {
+        if let Some(options) = self.inner.next_request() {
+            let body = self.agent().and_then(|agent| {
+                post_get_live_chat(&agent, options.clone(), self.inner.context())
+            });
+            self.inner.handle_get_live_chat(options, body);
+        }
+    }
+
+    fn agent(&mut self) -> Result<ureq::Agent, anyhow::Error> {
+        let proxy = self.inner.proxy();
+        if let Some((agent, agent_proxy)) = &self.agent {
+            if agent_proxy.as_deref() == proxy {
+                return Ok(agent.clone());
+            }
+        }
+        let new_agent = agent(proxy)?;
+        self.agent = Some((new_agent.clone(), proxy.map(str::to_string)));
+        Ok(new_agent)
+    }
+
+    /// Delivers the events still held by the reorder buffer, then calls `on_end`.
+    pub fn stop(&mut self) {
+        self.inner.stopped();
+    }
+
+    /// Starts the client unless it is started or resumed, then calls `execute` until
+    /// the stream ends and `stop`s, sleeping [`live_chat::LiveChatClient::poll_delay`]
+    /// between requests.
+    pub fn run(&mut self, interval: Duration) -> Result<(), anyhow::Error> {
+        if !self.inner.is_started() {
+            self.start()?;
+        }
+        loop {
+            self.execute();
+            if self.inner.has_ended() {
+                break;
+            }
+            thread::sleep(self.inner.poll_delay(interval));
+        }
+        self.stop();
+        Ok(())
+    }
+}
+
+impl<SF, ENF, CF, ERF, SCF, STF, MF, DF, GF, TF, BF, RDF, PF, EGF, UF, RAF, EVF> Deref
+    for LiveChatClient<SF, ENF, CF, ERF, SCF, STF, MF, DF, GF, TF, BF, RDF, PF, EGF, UF, RAF, EVF>
+where
+    SF: InvokeOnStart,
+    ENF: InvokeOnEnd,
+    CF: InvokeOnChat,
+    ERF: InvokeOnError,
+    SCF: InvokeOnSuperchat,
+    STF: InvokeOnSticker,
+    MF: InvokeOnMembership,
+    DF: InvokeOnDeletion,
+    GF: InvokeOnGift,
+    TF: InvokeOnTicker,
+    BF: InvokeOnBanner,
+    RDF: InvokeOnRaid,
+    PF: InvokeOnPoll,
+    EGF: InvokeOnEngagement,
+    UF: InvokeOnUnknown,
+    RAF: InvokeOnRawAction,
+    EVF: InvokeOnEvent,
+{
+    type Target = live_chat::LiveChatClient<
+        SF,
+        ENF,
+        CF,
+        ERF,
+        SCF,
+        STF,
+        MF,
+        DF,
+        GF,
+        TF,
+        BF,
+        RDF,
+        PF,
+        EGF,
+        UF,
+        RAF,
+        EVF,
+    >;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<SF, ENF, CF, ERF, SCF, STF, MF, DF, GF, TF, BF, RDF, PF, EGF, UF, RAF, EVF>
+    LiveChatClientBuilder<
+        String,
+        SF,
+        ENF,
+        CF,
+        ERF,
+        SCF,
+        STF,
+        MF,
+        DF,
+        GF,
+        TF,
+        BF,
+        RDF,
+        PF,
+        EGF,
+        UF,
+        RAF,
+        EVF,
+    >
+where
+    SF: InvokeOnStart,
+    ENF: InvokeOnEnd,
+    CF: InvokeOnChat,
+    ERF: InvokeOnError,
+    SCF: InvokeOnSuperchat,
+    STF: InvokeOnSticker,
+    MF: InvokeOnMembership,
+    DF: InvokeOnDeletion,
+    GF: InvokeOnGift,
+    TF: InvokeOnTicker,
+    BF: InvokeOnBanner,
+    RDF: InvokeOnRaid,
+    PF: InvokeOnPoll,
+    EGF: InvokeOnEngagement,
+    UF: InvokeOnUnknown,
+    RAF: InvokeOnRawAction,
+    EVF: InvokeOnEvent,
+{
+    #[allow(clippy::type_complexity)]
+    pub fn build_blocking(
+        self,
+    ) -> LiveChatClient<SF, ENF, CF, ERF, SCF, STF, MF, DF, GF, TF, BF, RDF, PF, EGF, UF, RAF, EVF>
+    {
+        LiveChatClient {
+            inner: self.build(),
+            agent: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod blocking_tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+
+    #[test]
+    fn test_execute_before_start() {
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let on_error = {
+            let errors = errors.clone();
+            move |err: anyhow::Error| errors.borrow_mut().push(err.to_string())
+        };
+        let mut client = LiveChatClientBuilder::new()
+            .live_id("jfKfPfyJRdk".to_string())
+            .on_error(on_error)
+            .build_blocking();
+        assert!(!client.is_started());
+        client.execute();
+        assert_eq!(errors.borrow().len(), 1);
+        assert!(client.checkpoint().is_none());
+    }
+}
@@ -0,0 +1,308 @@
+use std::{collections::BTreeMap, fmt};
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_ignored::Path;
+use serde_json::value::RawValue;
+
+use crate::youtube_types::{Action, ActionItem, GetLiveChatResponse};
+
+const ACTIONS_PATH: &str = "continuationContents.liveChatContinuation.actions[]";
+
+/// JSON that `youtube_types` does not model, collected from `get_live_chat` responses.
+/// Paths are dot separated with `[]` for array elements, e.g.
+/// `continuationContents.liveChatContinuation.actions[].addChatItemAction.item.fooRenderer`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SchemaDriftReport {
+    /// Number of responses inspected.
+    pub responses: usize,
+    /// Paths of unknown renderers, actions and commands with how often they were seen.
+    pub renderers: BTreeMap<String, usize>,
+    /// Paths of other unknown fields with how often they were seen.
+    pub fields: BTreeMap<String, usize>,
+}
+
+impl SchemaDriftReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records every path of `response` that deserializing it ignores.
+    /// Actions are inspected one by one so that a malformed action does not hide the others.
+    pub fn inspect(&mut self, mut response: serde_json::Value) {
+        self.responses += 1;
+        let actions = response
+            .pointer_mut("/continuationContents/liveChatContinuation/actions")
+            .map(serde_json::Value::take);
+        // Deserialization errors are reported by the parser, only ignored paths matter here.
+        let _ = self.record::<_, GetLiveChatResponse>(response, "");
+        if let Some(serde_json::Value::Array(actions)) = actions {
+            for action in actions {
+                if let Ok(action) = self.record::<_, Action>(action, ACTIONS_PATH) {
+                    self.record_action_extras(&action, ACTIONS_PATH);
+                }
+            }
+        }
+    }
+
+    /// Deserializes a `get_live_chat` response body, recording the paths of the
+    /// response that are ignored. Its actions are left to [`Self::record_action`].
+    pub(crate) fn record_response<'a>(
+        &mut self,
+        body: &'a [u8],
+    ) -> Result<GetLiveChatResponse<&'a RawValue>, serde_json::Error> {
+        self.responses += 1;
+        let mut deserializer = serde_json::Deserializer::from_slice(body);
+        let response = self.record(&mut deserializer, "")?;
+        deserializer.end()?;
+        Ok(response)
+    }
+
+    /// Deserializes an action of a response, recording the paths that are ignored.
+    pub(crate) fn record_action(&mut self, action: &RawValue) -> Result<Action, serde_json::Error> {
+        let action: Action = self.record(
+            &mut serde_json::Deserializer::from_str(action.get()),
+            ACTIONS_PATH,
+        )?;
+        self.record_action_extras(&action, ACTIONS_PATH);
+        Ok(action)
+    }
+
+    fn record<'de, D: Deserializer<'de>, T: Deserialize<'de>>(
+        &mut self,
+        deserializer: D,
+        prefix: &str,
+    ) -> Result<T, D::Error> {
+        serde_ignored::deserialize(deserializer, |path| {
+            let mut joined = prefix.to_string();
+            push_path(&mut joined, &path);
+            self.count(joined);
+        })
+    }
+
+    /// `serde_ignored` cannot see through `#[serde(flatten)]`, so renderers with flattened
+    /// fields keep their unknown keys in `extra` instead.
+    fn record_action_extras(&mut self, action: &Action, prefix: &str) {
+        if let Some(add) = &action.add_chat_item_action {
+            self.record_item_extras(&add.item, &format!("{}.addChatItemAction.item", prefix));
+        }
+        if let Some(add) = &action.add_live_chat_ticker_item_action {
+            let prefix = format!("{}.addLiveChatTickerItemAction.item", prefix);
+            let item = &add.item;
+            let tickers = [
+                item.live_chat_ticker_paid_message_item_renderer
+                    .as_ref()
+                    .map(|r| {
+                        (
+                            "liveChatTickerPaidMessageItemRenderer",
+                            &r.ticker_renderer_base,
+                            &r.extra,
+                        )
+                    }),
+                item.live_chat_ticker_paid_sticker_item_renderer
+                    .as_ref()
+                    .map(|r| {
+                        (
+                            "liveChatTickerPaidStickerItemRenderer",
+                            &r.ticker_renderer_base,
+                            &r.extra,
+                        )
+                    }),
+                item.live_chat_ticker_sponsor_item_renderer
+                    .as_ref()
+                    .map(|r| {
+                        (
+                            "liveChatTickerSponsorItemRenderer",
+                            &r.ticker_renderer_base,
+                            &r.extra,
+                        )
+                    }),
+            ];
+            for (name, base, extra) in tickers.into_iter().flatten() {
+                let prefix = format!("{}.{}", prefix, name);
+                self.record_extra(extra, &prefix);
+                if let Some(endpoint) = &base.show_item_endpoint {
+                    self.record_item_extras(
+                        &endpoint.show_live_chat_item_endpoint.renderer,
+                        &format!(
+                            "{}.showItemEndpoint.showLiveChatItemEndpoint.renderer",
+                            prefix
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn record_item_extras(&mut self, item: &ActionItem, prefix: &str) {
+        let extras = [
+            item.live_chat_text_message_renderer
+                .as_ref()
+                .map(|r| ("liveChatTextMessageRenderer", &r.extra)),
+            // Keys unknown to a paid message land in the flattened text message renderer.
+            item.live_chat_paid_message_renderer.as_ref().map(|r| {
+                (
+                    "liveChatPaidMessageRenderer",
+                    &r.live_chat_text_message_renderer.extra,
+                )
+            }),
+            item.live_chat_membership_item_renderer
+                .as_ref()
+                .map(|r| ("liveChatMembershipItemRenderer", &r.extra)),
+            item.live_chat_paid_sticker_renderer
+                .as_ref()
+                .map(|r| ("liveChatPaidStickerRenderer", &r.extra)),
+        ];
+        for (name, extra) in extras.into_iter().flatten() {
+            self.record_extra(extra, &format!("{}.{}", prefix, name));
+        }
+    }
+
+    fn record_extra(&mut self, extra: &serde_json::Map<String, serde_json::Value>, prefix: &str) {
+        for key in extra.keys() {
+            self.count(format!("{}.{}", prefix, key));
+        }
+    }
+
+    fn count(&mut self, path: String) {
+        let key = path.rsplit('.').next().unwrap_or_default();
+        let counts = if ["Renderer", "Action", "Command"]
+            .iter()
+            .any(|suffix| key.ends_with(suffix))
+        {
+            &mut self.renderers
+        } else {
+            &mut self.fields
+        };
+        *counts.entry(path).or_default() += 1;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.renderers.is_empty() && self.fields.is_empty()
+    }
+
+    /// Paths in this report that `baseline` has not seen, e.g. a report saved from a previous session.
+    pub fn new_since<'a>(&'a self, baseline: &'a Self) -> impl Iterator<Item = &'a str> {
+        self.renderers
+            .keys()
+            .filter(|path| !baseline.renderers.contains_key(*path))
+            .chain(
+                self.fields
+                    .keys()
+                    .filter(|path| !baseline.fields.contains_key(*path)),
+            )
+            .map(String::as_str)
+    }
+}
+
+fn push_path(joined: &mut String, path: &Path) {
+    match path {
+        Path::Root => {}
+        Path::Seq { parent, .. } => {
+            push_path(joined, parent);
+            joined.push_str("[]");
+        }
+        Path::Map { parent, key } => {
+            push_path(joined, parent);
+            if !joined.is_empty() {
+                joined.push('.');
+            }
+            joined.push_str(key);
+        }
+        Path::Some { parent }
+        | Path::NewtypeStruct { parent }
+        | Path::NewtypeVariant { parent } => push_path(joined, parent),
+    }
+}
+
+impl fmt::Display for SchemaDriftReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} responses inspected", self.responses)?;
+        writeln!(f, "unknown renderers: {}", self.renderers.len())?;
+        for (path, count) in &self.renderers {
+            writeln!(f, "  {} x{}", path, count)?;
+        }
+        writeln!(f, "unknown fields: {}", self.fields.len())?;
+        for (path, count) in &self.fields {
+            writeln!(f, "  {} x{}", path, count)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod diagnostics_tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_inspect() {
+        let response = json!({
+            "responseContext": {},
+            "newTopLevelField": 1,
+            "continuationContents": { "liveChatContinuation": {
+                "continuations": [],
+                "actions": [
+                    { "addChatItemAction": { "item": { "liveChatFancyRenderer": {} } } },
+                    { "addChatItemAction": { "item": { "liveChatFancyRenderer": {} } } },
+                    { "brandNewAction": {} }
+                ]
+            } }
+        });
+        let body = serde_json::to_vec(&response).unwrap();
+        let mut report = SchemaDriftReport::new();
+        report.inspect(response);
+        let mut parsed = SchemaDriftReport::new();
+        crate::parser::parse_slice(&body, &Default::default(), Some(&mut parsed)).unwrap();
+        assert_eq!(report.to_string(), parsed.to_string());
+        assert_eq!(report.responses, 1);
+        assert_eq!(
+            report.renderers[&format!(
+                "{}.addChatItemAction.item.liveChatFancyRenderer",
+                ACTIONS_PATH
+            )],
+            2
+        );
+        assert_eq!(
+            report.renderers[&format!("{}.brandNewAction", ACTIONS_PATH)],
+            1
+        );
+        assert_eq!(report.fields["newTopLevelField"], 1);
+        assert_eq!(report.new_since(&SchemaDriftReport::new()).count(), 3);
+    }
+
+    #[test]
+    fn test_inspect_flattened_renderer() {
+        let response = json!({
+            "responseContext": {},
+            "continuationContents": { "liveChatContinuation": {
+                "continuations": [],
+                "actions": [{
+                    "clickTrackingParams": "params",
+                    "addChatItemAction": { "item": { "liveChatTextMessageRenderer": {
+                        "message": { "runs": [{ "text": "hello" }] },
+                        "authorPhoto": { "thumbnails": [] },
+                        "contextMenuEndpoint": {
+                            "commandMetadata": { "webCommandMetadata": { "ignoreNavigation": true } },
+                            "liveChatItemContextMenuEndpoint": { "params": "params" }
+                        },
+                        "id": "a",
+                        "timestampUsec": "1676000000000000",
+                        "authorExternalChannelId": "UCxxxxxxxxxxxxxxxxxxxxxx",
+                        "contextMenuAccessibility": { "accessibilityData": { "label": "Chat actions" } },
+                        "newMessageField": true
+                    } } }
+                }]
+            } }
+        });
+        let mut report = SchemaDriftReport::new();
+        report.inspect(response);
+        assert!(report.renderers.is_empty());
+        assert_eq!(
+            report.fields.keys().collect::<Vec<_>>(),
+            [&format!(
+                "{}.addChatItemAction.item.liveChatTextMessageRenderer.newMessageField",
+                ACTIONS_PATH
+            )]
+        );
+    }
+}
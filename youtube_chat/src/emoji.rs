@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::item::{ChatItem, EmojiItem, ImageItem, MessageItem};
+
+/// A custom or member emoji collected by [`EmojiCatalog`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogEmoji {
+    pub id: String,
+    pub shortcuts: Vec<String>,
+    pub search_terms: Vec<String>,
+    pub supports_skin_tone: bool,
+    pub image: Option<ImageItem>,
+    /// Channel owning the emoji, taken from ids like `UC.../<emoji>`.
+    pub channel_id: Option<String>,
+}
+
+/// Custom emojis seen in chat items, keyed by emoji id.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EmojiCatalog {
+    emojis: BTreeMap<String, CatalogEmoji>,
+}
+
+impl EmojiCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `emoji` if it is a custom emoji, merging its shortcuts and search
+    /// terms into an already known entry. Returns whether it was new.
+    pub fn insert(&mut self, emoji: &EmojiItem) -> bool {
+        let Some(id) = &emoji.emoji_id else {
+            return false;
+        };
+        if emoji.is_custome_emoji != Some(true) && !id.contains('/') {
+            return false;
+        }
+        match self.emojis.get_mut(id) {
+            Some(known) => {
+                merge(&mut known.shortcuts, &emoji.shortcuts);
+                merge(&mut known.search_terms, &emoji.search_terms);
+                if emoji.image_item.is_some() {
+                    known.image.clone_from(&emoji.image_item);
+                }
+                false
+            }
+            None => {
+                let channel_id = id
+                    .split_once('/')
+                    .map(|(channel_id, _)| channel_id.to_string());
+                self.emojis.insert(
+                    id.clone(),
+                    CatalogEmoji {
+                        id: id.clone(),
+                        shortcuts: emoji.shortcuts.clone(),
+                        search_terms: emoji.search_terms.clone(),
+                        supports_skin_tone: emoji.supports_skin_tone == Some(true),
+                        image: emoji.image_item.clone(),
+                        channel_id,
+                    },
+                );
+                true
+            }
+        }
+    }
+
+    /// Adds every custom emoji of the message of `chat_item`.
+    pub fn add_chat_item(&mut self, chat_item: &ChatItem) {
+        for message_item in &chat_item.message {
+            if let MessageItem::Emoji(emoji) = message_item {
+                self.insert(emoji);
+            }
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&CatalogEmoji> {
+        self.emojis.get(id)
+    }
+
+    pub fn find_by_shortcut(&self, shortcut: &str) -> Option<&CatalogEmoji> {
+        self.iter()
+            .find(|emoji| emoji.shortcuts.iter().any(|known| known == shortcut))
+    }
+
+    /// Emojis whose shortcuts or search terms contain `term`, ignoring case.
+    pub fn search<'a>(&'a self, term: &str) -> impl Iterator<Item = &'a CatalogEmoji> {
+        let term = term.to_lowercase();
+        self.iter().filter(move |emoji| {
+            emoji
+                .shortcuts
+                .iter()
+                .chain(&emoji.search_terms)
+                .any(|text| text.to_lowercase().contains(&term))
+        })
+    }
+
+    pub fn by_channel<'a>(&'a self, channel_id: &'a str) -> impl Iterator<Item = &'a CatalogEmoji> {
+        self.iter()
+            .filter(move |emoji| emoji.channel_id.as_deref() == Some(channel_id))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &CatalogEmoji> {
+        self.emojis.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.emojis.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.emojis.is_empty()
+    }
+
+    /// Serializes the catalog as a JSON array of [`CatalogEmoji`].
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&self.iter().collect::<Vec<_>>())
+    }
+}
+
+fn merge(known: &mut Vec<String>, new: &[String]) {
+    for text in new {
+        if !known.contains(text) {
+            known.push(text.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod emoji_tests {
+    use super::*;
+
+    fn emoji(id: &str, shortcut: &str, is_custom: bool) -> EmojiItem {
+        EmojiItem {
+            image_item: Some(ImageItem {
+                url: "https://yt3.ggpht.com/emoji=w24-h24-c-k-nd".to_string(),
+                alt: Some(shortcut.to_string()),
+                sizes: Vec::new(),
+            }),
+            emoji_text: Some(shortcut.to_string()),
+            is_custome_emoji: Some(is_custom),
+            emoji_id: Some(id.to_string()),
+            shortcuts: vec![shortcut.to_string()],
+            search_terms: vec![shortcut.trim_matches(':').to_string()],
+            supports_skin_tone: Some(false),
+        }
+    }
+
+    #[test]
+    fn test_emoji_catalog() {
+        let mut catalog = EmojiCatalog::new();
+        assert!(catalog.insert(&emoji("UCowner/wave", ":_wave:", true)));
+        assert!(!catalog.insert(&emoji("UCowner/wave", ":_hello:", true)));
+        assert!(!catalog.insert(&emoji("😀", ":grinning_face:", false)));
+        assert_eq!(catalog.len(), 1);
+
+        let wave = catalog.find_by_shortcut(":_hello:").unwrap();
+        assert_eq!(wave.shortcuts, [":_wave:", ":_hello:"]);
+        assert_eq!(wave.channel_id.as_deref(), Some("UCowner"));
+        assert_eq!(catalog.search("HELLO").count(), 1);
+        assert_eq!(catalog.by_channel("UCowner").count(), 1);
+
+        let json: serde_json::Value = serde_json::from_str(&catalog.to_json().unwrap()).unwrap();
+        assert_eq!(json[0]["id"], "UCowner/wave");
+    }
+}
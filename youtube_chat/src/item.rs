@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use chrono::{DateTime, Utc};
 use serde::{self, Deserialize, Serialize};
 
@@ -7,6 +9,7 @@ pub struct ChatItem {
     pub author: Author,
     pub message: Vec<MessageItem>,
     pub superchat: Option<SuperChat>,
+    pub membership: Option<Membership>,
     pub is_membership: bool,
     pub is_verified: bool,
     pub is_owner: bool,
@@ -26,12 +29,92 @@ pub struct Author {
 pub enum MessageItem {
     Text(String),
     Emoji(EmojiItem),
+    /// Bold or italic text. Unformatted text stays [`MessageItem::Text`].
+    StyledText {
+        text: String,
+        style: TextStyle,
+    },
+    /// A link, with YouTube's redirect URL already resolved to the target.
+    Link {
+        text: String,
+        url: String,
+        style: TextStyle,
+    },
+    /// A mention of a channel, e.g. "@name".
+    Mention {
+        text: String,
+        channel_id: Option<String>,
+        style: TextStyle,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextStyle {
+    pub bold: bool,
+    pub italics: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImageItem {
     pub url: String,
     pub alt: Option<String>,
+    /// Every resolution YouTube sent, `url` being the first one.
+    #[serde(default)]
+    pub sizes: Vec<ImageSize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageSize {
+    pub url: String,
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+}
+
+impl ImageItem {
+    /// Returns the smallest image at least `size` pixels wide, or the widest one
+    /// if none is large enough.
+    pub fn best_for(&self, size: usize) -> &str {
+        let widths = self
+            .sizes
+            .iter()
+            .filter_map(|image| Some((image.width?, image.url.as_str())));
+        widths
+            .clone()
+            .filter(|(width, _)| *width >= size)
+            .min_by_key(|(width, _)| *width)
+            .or_else(|| widths.max_by_key(|(width, _)| *width))
+            .map_or(&self.url, |(_, url)| url)
+    }
+
+    /// Returns `url` rewritten to request a `size` pixel image, or `None` if it
+    /// has no `=sNN` or `=wNN-hNN` size suffix.
+    pub fn url_with_size(&self, size: usize) -> Option<String> {
+        resize_url(&self.url, size)
+    }
+}
+
+/// Rewrites the size options after the last `=` of a YouTube image URL,
+/// e.g. `...=s64-c-k-nd` or `...=w24-h24-c-k-nd`, keeping the other options.
+pub fn resize_url(url: &str, size: usize) -> Option<String> {
+    let (base, options) = url.rsplit_once('=')?;
+    let is_size = |option: &str, prefix: char| {
+        option.len() > 1
+            && option.starts_with(prefix)
+            && option[1..].bytes().all(|b| b.is_ascii_digit())
+    };
+    let options: Vec<&str> = options.split('-').collect();
+    let (mut resized, rest) = match options.as_slice() {
+        [s, rest @ ..] if is_size(s, 's') => (format!("{}=s{}", base, size), rest),
+        [w, h, rest @ ..] if is_size(w, 'w') && is_size(h, 'h') => {
+            (format!("{}=w{}-h{}", base, size, size), rest)
+        }
+        _ => return None,
+    };
+    for option in rest {
+        resized.push('-');
+        resized.push_str(option);
+    }
+    Some(resized)
 }
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmojiItem {
@@ -39,6 +122,13 @@ pub struct EmojiItem {
     pub image_item: Option<ImageItem>,
     pub emoji_text: Option<String>,
     pub is_custome_emoji: Option<bool>,
+    pub emoji_id: Option<String>,
+    /// Every shortcut of the emoji, e.g. `[":_smile:", ":smile:"]`.
+    #[serde(default)]
+    pub shortcuts: Vec<String>,
+    #[serde(default)]
+    pub search_terms: Vec<String>,
+    pub supports_skin_tone: Option<bool>,
 }
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Badge {
@@ -52,3 +142,334 @@ pub struct SuperChat {
     pub color: String,
     pub sticker: Option<ImageItem>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Membership {
+    pub kind: MembershipKind,
+    /// Name of the membership tier, when YouTube shows it.
+    pub tier: Option<String>,
+    /// Months of membership, only set for milestones.
+    pub months: Option<u32>,
+    /// Message the member wrote with a milestone, empty otherwise.
+    pub message: Vec<MessageItem>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MembershipKind {
+    New,
+    Milestone,
+    /// Told apart from [`MembershipKind::New`] only by the English header, e.g.
+    /// `Upgraded membership to <tier>!`, so upgrades are reported as `New` when
+    /// the language is not `en`.
+    Upgrade,
+}
+
+/// Everything `parser::parse_chat_events` can produce from a live chat action.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ChatEvent {
+    Chat(ChatItem),
+    SuperChat(ChatItem),
+    Sticker(ChatItem),
+    Membership(ChatItem),
+    Gift(GiftMembership),
+    Ticker(Ticker),
+    Banner(BannerEvent),
+    Raid(Raid),
+    Poll(PollEvent),
+    Engagement(Engagement),
+    Deletion(Deletion),
+    /// An action that could not be parsed, only produced by lenient parsing.
+    Unknown(UnknownAction),
+}
+
+impl ChatEvent {
+    pub fn chat_item(&self) -> Option<&ChatItem> {
+        match self {
+            ChatEvent::Chat(chat_item)
+            | ChatEvent::SuperChat(chat_item)
+            | ChatEvent::Sticker(chat_item)
+            | ChatEvent::Membership(chat_item) => Some(chat_item),
+            _ => None,
+        }
+    }
+
+    /// Id used for de-duplication. Banners and polls have none, since their
+    /// updates are sent again under the same id.
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            ChatEvent::Gift(gift) => Some(gift.id()),
+            ChatEvent::Ticker(ticker) => Some(&ticker.id),
+            ChatEvent::Engagement(engagement) => Some(&engagement.id),
+            ChatEvent::Banner(_)
+            | ChatEvent::Raid(_)
+            | ChatEvent::Poll(_)
+            | ChatEvent::Unknown(_) => None,
+            chat_event => chat_event
+                .chat_item()
+                .map(|chat_item| chat_item.id.as_str()),
+        }
+    }
+
+    pub fn timestamp(&self) -> Option<DateTime<Utc>> {
+        match self {
+            ChatEvent::Gift(gift) => gift.timestamp(),
+            ChatEvent::Engagement(engagement) => engagement.timestamp,
+            chat_event => chat_event.chat_item()?.timestamp,
+        }
+    }
+
+    pub fn into_chat_item(self) -> Option<ChatItem> {
+        match self {
+            ChatEvent::Chat(chat_item)
+            | ChatEvent::SuperChat(chat_item)
+            | ChatEvent::Sticker(chat_item)
+            | ChatEvent::Membership(chat_item) => Some(chat_item),
+            _ => None,
+        }
+    }
+}
+
+/// An item pinned to the ticker bar above the chat.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ticker {
+    pub id: String,
+    pub kind: TickerKind,
+    pub author: Author,
+    /// Purchase amount of paid message and paid sticker tickers.
+    pub amount: Option<String>,
+    /// Detail text of sponsor tickers, e.g. the number of gifted memberships.
+    pub detail: Option<String>,
+    pub sticker: Option<ImageItem>,
+    /// Time left before the ticker disappears.
+    pub duration: Duration,
+    /// Time the ticker is shown in total.
+    pub full_duration: Duration,
+    /// Id of the chat item the ticker opens when clicked.
+    pub chat_item_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TickerKind {
+    PaidMessage,
+    PaidSticker,
+    Sponsor,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BannerEvent {
+    /// A banner was pinned above the chat.
+    Added(Box<Banner>),
+    /// The banner added with `action_id` was removed.
+    Removed { action_id: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Banner {
+    pub action_id: String,
+    /// Header text, e.g. "Pinned by @owner".
+    pub header: Option<String>,
+    /// The pinned chat item, if the banner holds one.
+    pub chat_item: Option<ChatItem>,
+}
+
+/// A raid announced with a redirect banner.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Raid {
+    /// Id of the banner, referenced by [`BannerEvent::Removed`].
+    pub action_id: String,
+    /// Name of the raiding channel, e.g. "@raider".
+    pub from_channel: Option<String>,
+    /// Stream the raid leads to, when the banner links one.
+    pub to_video_id: Option<String>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PollEvent {
+    /// A poll was opened in the action panel `panel_id`.
+    Shown { panel_id: String, poll: Poll },
+    /// The votes of a poll changed.
+    Updated(Poll),
+    /// The action panel `panel_id` was closed, ending its poll.
+    Closed { panel_id: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Poll {
+    pub id: String,
+    pub question: Option<String>,
+    pub choices: Vec<PollChoice>,
+    /// e.g. "@owner • just now • 12 votes"
+    pub metadata: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollChoice {
+    pub text: String,
+    /// Share of votes between `0.0` and `1.0`, only known once results are shown.
+    pub vote_ratio: Option<f64>,
+    pub vote_percentage: Option<String>,
+}
+
+/// A notice from YouTube in the chat, e.g. the chat mode or welcome message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Engagement {
+    pub id: String,
+    pub icon: Option<String>,
+    pub message: Vec<MessageItem>,
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnknownAction {
+    /// Raw JSON of the action.
+    pub action: serde_json::Value,
+    /// Why the action was not parsed, e.g. an unsupported renderer or a serde error.
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Deletion {
+    /// A single chat item was deleted or retracted.
+    Item { id: String },
+    /// All chat items of the author were deleted, e.g. on a ban.
+    Author { channel_id: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum GiftMembership {
+    /// A viewer bought memberships for other viewers.
+    Purchase(GiftPurchase),
+    /// A viewer received a membership bought by someone else.
+    Redemption(GiftRedemption),
+}
+
+impl GiftMembership {
+    pub fn id(&self) -> &str {
+        match self {
+            GiftMembership::Purchase(purchase) => &purchase.id,
+            GiftMembership::Redemption(redemption) => &redemption.id,
+        }
+    }
+
+    pub fn timestamp(&self) -> Option<DateTime<Utc>> {
+        match self {
+            GiftMembership::Purchase(purchase) => purchase.timestamp,
+            GiftMembership::Redemption(redemption) => redemption.timestamp,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GiftPurchase {
+    pub id: String,
+    pub gifter: Author,
+    /// Number of memberships gifted.
+    pub count: u32,
+    /// Name of the channel the memberships belong to, read from the announcement text.
+    pub channel_name: Option<String>,
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GiftRedemption {
+    pub id: String,
+    pub recipient: Author,
+    /// Name of the viewer who bought the membership, read from the announcement text.
+    pub gifter_name: Option<String>,
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+/// Where and when to fetch the chat next, as given by a `get_live_chat` response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Continuation {
+    /// Live chat whose updates are announced on `topic`; fetch again within `timeout`.
+    Invalidation {
+        continuation: String,
+        timeout: Duration,
+        topic: String,
+    },
+    /// Live chat to fetch again after `timeout`.
+    Timed {
+        continuation: String,
+        timeout: Duration,
+    },
+    /// The chat has to be loaded again, e.g. after switching between top chat and live chat.
+    Reload { continuation: String },
+    /// Chat replay of a finished stream.
+    Replay {
+        continuation: String,
+        time_until_last_message: Option<Duration>,
+    },
+    /// Chat replay from the position the player seeked to.
+    PlayerSeek { continuation: String },
+}
+
+impl Continuation {
+    /// The token to send with the next `get_live_chat` request.
+    pub fn token(&self) -> &str {
+        match self {
+            Continuation::Invalidation { continuation, .. }
+            | Continuation::Timed { continuation, .. }
+            | Continuation::Reload { continuation }
+            | Continuation::Replay { continuation, .. }
+            | Continuation::PlayerSeek { continuation } => continuation,
+        }
+    }
+
+    pub fn into_token(self) -> String {
+        match self {
+            Continuation::Invalidation { continuation, .. }
+            | Continuation::Timed { continuation, .. }
+            | Continuation::Reload { continuation }
+            | Continuation::Replay { continuation, .. }
+            | Continuation::PlayerSeek { continuation } => continuation,
+        }
+    }
+
+    /// How long to wait at most before fetching again, for live chat continuations.
+    pub fn timeout(&self) -> Option<Duration> {
+        match self {
+            Continuation::Invalidation { timeout, .. } | Continuation::Timed { timeout, .. } => {
+                Some(*timeout)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod item_tests {
+    use super::*;
+
+    #[test]
+    fn test_image_sizes() {
+        let image = ImageItem {
+            url: "https://yt4.ggpht.com/photo=s32-c-k-c0x00ffffff-no-rj".to_string(),
+            alt: None,
+            sizes: [32, 64]
+                .into_iter()
+                .map(|size| ImageSize {
+                    url: format!(
+                        "https://yt4.ggpht.com/photo=s{}-c-k-c0x00ffffff-no-rj",
+                        size
+                    ),
+                    width: Some(size),
+                    height: Some(size),
+                })
+                .collect(),
+        };
+        assert!(image.best_for(48).contains("=s64-"));
+        assert!(image.best_for(128).contains("=s64-"));
+        assert!(image.best_for(16).contains("=s32-"));
+        assert_eq!(
+            image.url_with_size(128).unwrap(),
+            "https://yt4.ggpht.com/photo=s128-c-k-c0x00ffffff-no-rj"
+        );
+        assert_eq!(
+            resize_url("https://yt3.ggpht.com/emoji=w24-h24-c-k-nd", 48).unwrap(),
+            "https://yt3.ggpht.com/emoji=w48-h48-c-k-nd"
+        );
+        assert!(resize_url("https://www.gstatic.com/emoji.svg", 48).is_none());
+    }
+}
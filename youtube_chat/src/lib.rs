@@ -1,5 +1,24 @@
+// Without a client feature nothing sends requests, so the polling state of the client is unused.
+#![cfg_attr(
+    not(any(
+        feature = "reqwest",
+        feature = "isahc",
+        feature = "wasm",
+        feature = "blocking"
+    )),
+    allow(dead_code)
+)]
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod diagnostics;
+pub mod emoji;
 pub mod item;
 pub mod live_chat;
 pub mod parser;
+pub mod proxy;
+pub mod render;
 pub mod request;
+#[cfg(any(feature = "reqwest", feature = "isahc", feature = "wasm"))]
+pub mod runtime;
 pub mod youtube_types;
@@ -1,179 +1,667 @@
+use std::{
+    collections::{BTreeMap, HashSet, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
+
 use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use url::Url;
+use youtube_chat_macro::TypestateBuilder;
+
+#[cfg(any(feature = "reqwest", feature = "isahc", feature = "wasm"))]
+use crate::request::{fetch_live_page_through, post_get_live_chat};
 
 use crate::{
-    item::ChatItem,
-    request::{fetch_chat, fetch_live_page, RequestOptions},
+    diagnostics::SchemaDriftReport,
+    emoji::EmojiCatalog,
+    item::{
+        BannerEvent, ChatEvent, ChatItem, Continuation, Deletion, Engagement, GiftMembership,
+        PollEvent, Raid, Ticker, UnknownAction,
+    },
+    parser::{parse_continuation_from_slice, ChatRenderer, ParseOptions},
+    proxy::{ProxyRotation, ProxySession},
+    request::{parse_get_live_chat, ClientContext, RequestOptions, CONSENT_COOKIES},
 };
 
-/// SF, ENF, CF, ERF is `()` or `T: Fn()`
-pub struct LiveChatClient<SF, ENF, CF, ERF>
-where
+/// Each handler type parameter is `Empty` or `T: Fn(..)`
+pub struct LiveChatClient<
+    SF,
+    ENF,
+    CF,
+    ERF,
+    SCF,
+    STF,
+    MF,
+    DF,
+    GF,
+    TF,
+    BF,
+    RDF,
+    PF,
+    EGF,
+    UF,
+    RAF,
+    EVF,
+> where
     SF: InvokeOnStart,
     ENF: InvokeOnEnd,
     CF: InvokeOnChat,
     ERF: InvokeOnError,
+    SCF: InvokeOnSuperchat,
+    STF: InvokeOnSticker,
+    MF: InvokeOnMembership,
+    DF: InvokeOnDeletion,
+    GF: InvokeOnGift,
+    TF: InvokeOnTicker,
+    BF: InvokeOnBanner,
+    RDF: InvokeOnRaid,
+    PF: InvokeOnPoll,
+    EGF: InvokeOnEngagement,
+    UF: InvokeOnUnknown,
+    RAF: InvokeOnRawAction,
+    EVF: InvokeOnEvent,
 {
     live_url: String,
     on_start: SF,
     on_end: ENF,
     on_chat: CF,
     on_error: ERF,
+    on_superchat: SCF,
+    on_sticker: STF,
+    on_membership: MF,
+    on_deletion: DF,
+    on_gift: GF,
+    on_ticker: TF,
+    on_banner: BF,
+    on_raid: RDF,
+    on_poll: PF,
+    on_engagement: EGF,
+    on_unknown: UF,
+    on_raw_action: RAF,
+    on_event: EVF,
+    state: ClientState,
+}
+
+/// Serializable snapshot of a [`LiveChatClient`], used to resume polling after a restart.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub live_url: String,
+    pub options: RequestOptions,
+    pub last_message_ids: Vec<String>,
+    pub last_timestamp: Option<DateTime<Utc>>,
+    /// Items held back by the reorder buffer and not delivered yet.
+    #[serde(default)]
+    pub pending: Vec<ChatEvent>,
+}
+
+/// Number of recent message ids remembered for de-duplication by default.
+pub const DEFAULT_DEDUP_CAPACITY: usize = 2000;
+
+/// Counters of the de-duplication window of a [`LiveChatClient`]. Only events with an
+/// [`ChatEvent::id`], i.e. chat items, gift announcements, tickers and engagement messages,
+/// are counted; both counters restart on `resume_from`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DedupStats {
+    /// Events whose id was not in the window. They are passed on, possibly after
+    /// being held by the reorder buffer.
+    pub delivered: u64,
+    /// Events dropped because their id was still in the window.
+    pub suppressed: u64,
+}
+
+/// Bounded window of recently delivered message ids, oldest evicted first.
+struct DedupWindow {
+    capacity: usize,
+    order: VecDeque<String>,
+    ids: HashSet<String>,
+    stats: DedupStats,
+}
+
+impl DedupWindow {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            order: VecDeque::with_capacity(capacity),
+            ids: HashSet::with_capacity(capacity),
+            stats: DedupStats::default(),
+        }
+    }
+
+    /// Returns `false` if `id` is still in the window.
+    fn insert(&mut self, id: &str) -> bool {
+        if self.ids.contains(id) {
+            self.stats.suppressed += 1;
+            return false;
+        }
+        self.stats.delivered += 1;
+        if self.capacity == 0 {
+            return true;
+        }
+        self.evict_to(self.capacity - 1);
+        self.order.push_back(id.to_string());
+        self.ids.insert(id.to_string());
+        true
+    }
+
+    fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict_to(capacity);
+    }
+
+    fn evict_to(&mut self, len: usize) {
+        while self.order.len() > len {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+    }
+
+    fn ids(&self) -> Vec<String> {
+        self.order.iter().cloned().collect()
+    }
+}
+
+impl Default for DedupWindow {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEDUP_CAPACITY)
+    }
+}
+
+/// Holds chat events for `delay` and releases them sorted by timestamp.
+/// Events without a timestamp, or older than what was already released, are not held.
+struct ReorderBuffer {
+    /// `None` when too long for `chrono`, holding events until they are drained.
+    delay: Option<chrono::Duration>,
+    items: BTreeMap<(DateTime<Utc>, u64), ChatEvent>,
+    sequence: u64,
+    released_until: Option<DateTime<Utc>>,
+}
+
+impl ReorderBuffer {
+    fn new(delay: Duration) -> Self {
+        Self {
+            delay: chrono::Duration::from_std(delay).ok(),
+            items: BTreeMap::new(),
+            sequence: 0,
+            released_until: None,
+        }
+    }
+
+    /// Buffers `chat_events` and returns those that are due at `now`.
+    fn push(&mut self, chat_events: Vec<ChatEvent>, now: DateTime<Utc>) -> Vec<ChatEvent> {
+        let mut ready = Vec::new();
+        for chat_event in chat_events {
+            match chat_event.timestamp() {
+                Some(timestamp) if self.released_until.is_none_or(|until| timestamp >= until) => {
+                    self.items.insert((timestamp, self.sequence), chat_event);
+                    self.sequence += 1;
+                }
+                _ => ready.push(chat_event),
+            }
+        }
+        let cutoff = self
+            .delay
+            .and_then(|delay| now.checked_sub_signed(delay))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        let later = self.items.split_off(&(cutoff, u64::MAX));
+        ready.extend(self.release(later));
+        ready
+    }
+
+    fn drain(&mut self) -> Vec<ChatEvent> {
+        self.release(BTreeMap::new())
+    }
+
+    fn release(&mut self, keep: BTreeMap<(DateTime<Utc>, u64), ChatEvent>) -> Vec<ChatEvent> {
+        let released = std::mem::replace(&mut self.items, keep);
+        if let Some(&(timestamp, _)) = released.keys().next_back() {
+            self.released_until = Some(timestamp);
+        }
+        released.into_values().collect()
+    }
+
+    fn pending(&self) -> Vec<ChatEvent> {
+        self.items.values().cloned().collect()
+    }
+}
+
+#[derive(Default)]
+struct ClientState {
     options: Option<RequestOptions>,
+    dedup: DedupWindow,
+    reorder: Option<ReorderBuffer>,
+    /// Pending items restored from a checkpoint, already de-duplicated.
+    restored: Vec<ChatEvent>,
+    last_timestamp: Option<DateTime<Utc>>,
+    emoji_catalog: EmojiCatalog,
+    parse_options: ParseOptions,
+    drift: Option<SchemaDriftReport>,
+    continuation: Option<Continuation>,
+    proxy: Option<ProxySession>,
+    context: ClientContext,
 }
 
-impl<SF, ENF, CF, ERF> LiveChatClient<SF, ENF, CF, ERF>
+impl ClientState {
+    fn resume(&mut self, checkpoint: Checkpoint) {
+        self.options = Some(checkpoint.options);
+        self.dedup = DedupWindow::new(self.dedup.capacity);
+        for id in checkpoint.last_message_ids {
+            self.dedup.insert(&id);
+        }
+        self.dedup.stats = DedupStats::default();
+        self.last_timestamp = checkpoint.last_timestamp;
+        self.restored = checkpoint.pending;
+    }
+
+    /// Returns the items of a fetched batch that should be delivered now.
+    fn accept(&mut self, chat_events: Vec<ChatEvent>, now: DateTime<Utc>) -> Vec<ChatEvent> {
+        let mut unseen = std::mem::take(&mut self.restored);
+        unseen.extend(self.take_unseen(chat_events));
+        match &mut self.reorder {
+            Some(reorder) => reorder.push(unseen, now),
+            None => unseen,
+        }
+    }
+
+    fn pending(&self) -> Vec<ChatEvent> {
+        let mut pending = self.restored.clone();
+        if let Some(reorder) = &self.reorder {
+            pending.extend(reorder.pending());
+        }
+        pending
+    }
+
+    /// Drops events whose [`ChatEvent::id`] is still in the de-duplication window.
+    fn take_unseen(&mut self, chat_events: Vec<ChatEvent>) -> Vec<ChatEvent> {
+        self.last_timestamp = chat_events
+            .iter()
+            .filter_map(ChatEvent::timestamp)
+            .chain(self.last_timestamp)
+            .max();
+        let unseen: Vec<ChatEvent> = chat_events
+            .into_iter()
+            .filter(|chat_event| match chat_event.id() {
+                Some(id) => self.dedup.insert(id),
+                None => true,
+            })
+            .collect();
+        for chat_item in unseen.iter().filter_map(ChatEvent::chat_item) {
+            self.emoji_catalog.add_chat_item(chat_item);
+        }
+        unseen
+    }
+}
+
+impl<SF, ENF, CF, ERF, SCF, STF, MF, DF, GF, TF, BF, RDF, PF, EGF, UF, RAF, EVF>
+    LiveChatClient<SF, ENF, CF, ERF, SCF, STF, MF, DF, GF, TF, BF, RDF, PF, EGF, UF, RAF, EVF>
 where
     SF: InvokeOnStart,
     ENF: InvokeOnEnd,
     CF: InvokeOnChat,
     ERF: InvokeOnError,
+    SCF: InvokeOnSuperchat,
+    STF: InvokeOnSticker,
+    MF: InvokeOnMembership,
+    DF: InvokeOnDeletion,
+    GF: InvokeOnGift,
+    TF: InvokeOnTicker,
+    BF: InvokeOnBanner,
+    RDF: InvokeOnRaid,
+    PF: InvokeOnPoll,
+    EGF: InvokeOnEngagement,
+    UF: InvokeOnUnknown,
+    RAF: InvokeOnRawAction,
+    EVF: InvokeOnEvent,
 {
+    #[cfg(any(feature = "reqwest", feature = "isahc", feature = "wasm"))]
     pub async fn execute(&mut self) {
-        if let Some(mut options) = self.options.clone() {
-            let result: Result<(), anyhow::Error> = async {
-                let (chat_items, continuation) = fetch_chat(options.clone()).await?;
-                for chat_item in chat_items {
-                    self.invoke_on_chat(chat_item);
-                }
-                options.continuation = continuation;
-                self.options = Some(options);
-                Ok(())
-            }
-            .await;
-            if let Err(err) = result {
-                self.invoke_on_error(err);
+        if let Some(options) = self.next_request() {
+            let proxy = self.proxy().map(str::to_string);
+            let body =
+                post_get_live_chat(options.clone(), &self.state.context, proxy.as_deref()).await;
+            self.handle_get_live_chat(options, body);
+        }
+    }
+
+    #[cfg(any(feature = "reqwest", feature = "isahc", feature = "wasm"))]
+    pub async fn start(&mut self) -> Result<(), anyhow::Error> {
+        let proxy = self.proxy().map(str::to_string);
+        let result =
+            fetch_live_page_through(self.live_url.clone(), &self.state.context, proxy.as_deref())
+                .await;
+        self.record_request(result.is_ok());
+        let (options, live_id) = result?;
+        self.started(options, live_id);
+        Ok(())
+    }
+
+    /// Delivers the events still held by the reorder buffer, then calls `on_end`.
+    #[cfg(any(feature = "reqwest", feature = "isahc", feature = "wasm"))]
+    pub async fn stop(&mut self) -> Result<(), anyhow::Error> {
+        self.stopped();
+        Ok(())
+    }
+
+    /// Starts the client unless it is started or resumed, then calls `execute` until
+    /// the stream ends and `stop`s, waiting [`Self::poll_delay`] between requests.
+    #[cfg(any(feature = "reqwest", feature = "isahc", feature = "wasm"))]
+    pub async fn run(&mut self, interval: Duration) -> Result<(), anyhow::Error> {
+        self.run_with_sleep(interval, crate::runtime::sleep).await
+    }
+
+    /// Like [`Self::run`], waiting with `sleep`, e.g. `async_std::task::sleep`,
+    /// on runtimes whose timer [`crate::runtime::sleep`] does not use.
+    #[cfg(any(feature = "reqwest", feature = "isahc", feature = "wasm"))]
+    pub async fn run_with_sleep<S, F>(
+        &mut self,
+        interval: Duration,
+        sleep: S,
+    ) -> Result<(), anyhow::Error>
+    where
+        S: Fn(Duration) -> F,
+        F: std::future::Future<Output = ()>,
+    {
+        if !self.is_started() {
+            self.start().await?;
+        }
+        loop {
+            self.execute().await;
+            if self.has_ended() {
+                break;
             }
-        } else {
+            sleep(self.poll_delay(interval)).await;
+        }
+        self.stopped();
+        Ok(())
+    }
+
+    /// How long to wait before the next `execute`: as long as the last response
+    /// asks, `interval` at most.
+    pub fn poll_delay(&self, interval: Duration) -> Duration {
+        self.continuation()
+            .and_then(Continuation::timeout)
+            .map_or(interval, |timeout| timeout.min(interval))
+    }
+
+    /// Returns the options of the next `get_live_chat` request, or calls `on_error`
+    /// if the client is not started.
+    pub(crate) fn next_request(&self) -> Option<RequestOptions> {
+        let options = self.state.options.clone();
+        if options.is_none() {
             self.invoke_on_error(anyhow!(
                 "This client is not ready for execute, just call `start`"
             ));
         }
+        options
     }
 
-    pub async fn start(&mut self) -> Result<(), anyhow::Error> {
-        let (options, live_id) = fetch_live_page(self.live_url.clone()).await?;
-        self.options = Some(options);
-        self.invoke_on_start(live_id);
+    /// Delivers the events of the response to the request made with `options`
+    /// and continues from it, or calls `on_error`.
+    pub(crate) fn handle_get_live_chat(
+        &mut self,
+        options: RequestOptions,
+        body: Result<impl AsRef<[u8]>, anyhow::Error>,
+    ) {
+        self.record_request(body.is_ok());
+        let result = body.and_then(|body| self.deliver(options, body.as_ref()));
+        if let Err(err) = result {
+            self.invoke_on_error(err);
+        }
+    }
+
+    fn deliver(&mut self, mut options: RequestOptions, body: &[u8]) -> Result<(), anyhow::Error> {
+        let batch = parse_get_live_chat(body, &self.state.parse_options, self.state.drift.as_mut());
+        // Move past a page whose actions fail to parse instead of requesting it again.
+        let continuation = match &batch {
+            Ok((_, _, continuation)) => continuation.clone(),
+            Err(_) => parse_continuation_from_slice(body),
+        };
+        if batch.is_ok() || continuation.is_some() {
+            options.continuation = continuation
+                .as_ref()
+                .map(|continuation| continuation.token().to_string())
+                .unwrap_or_default();
+            self.state.options = Some(options);
+            self.state.continuation = continuation;
+        }
+        let (chat_events, raw_actions, _) = batch?;
+        // The raw actions are only slices of `body` until a handler asks for them.
+        if RAF::IS_SET {
+            for raw_action in raw_actions {
+                self.invoke_on_raw_action(serde_json::from_str(raw_action.get())?);
+            }
+        }
+        for chat_event in self.state.accept(chat_events, Utc::now()) {
+            self.dispatch(chat_event);
+        }
         Ok(())
     }
 
-    pub async fn stop(&mut self) -> Result<(), anyhow::Error> {
-        self.options = None;
+    /// The proxy the next request goes through, for requests sent with another HTTP client.
+    pub fn proxy(&self) -> Option<&str> {
+        self.state.proxy.as_ref().and_then(ProxySession::proxy)
+    }
+
+    /// Language, region and headers of the requests, for requests sent with another HTTP client.
+    pub fn context(&self) -> &ClientContext {
+        &self.state.context
+    }
+
+    /// Counts failed requests for the proxy rotation.
+    pub(crate) fn record_request(&mut self, succeeded: bool) {
+        if let Some(proxy) = &mut self.state.proxy {
+            proxy.record(succeeded);
+        }
+    }
+
+    pub fn live_url(&self) -> &str {
+        &self.live_url
+    }
+
+    /// Starts the client with the options read from the live page, see
+    /// [`parser::get_options_from_live_page`](crate::parser::get_options_from_live_page).
+    pub(crate) fn started(&mut self, options: RequestOptions, live_id: String) {
+        self.state.options = Some(options);
+        self.invoke_on_start(live_id);
+    }
+
+    /// Delivers the events still held by the reorder buffer, then calls `on_end`.
+    pub(crate) fn stopped(&mut self) {
+        let mut pending = std::mem::take(&mut self.state.restored);
+        if let Some(reorder) = &mut self.state.reorder {
+            pending.extend(reorder.drain());
+        }
+        for chat_event in pending {
+            self.dispatch(chat_event);
+        }
+        self.state.options = None;
         self.invoke_on_end();
-        Ok(())
     }
-}
 
-pub struct Empty;
-pub trait InvokeOnStart {
-    fn invoke_on_start(&self, _live_id: String) {}
-}
-pub trait InvokeOnEnd {
-    fn invoke_on_end(&self) {}
-}
-pub trait InvokeOnChat {
-    fn invoke_on_chat(&self, _chat_item: ChatItem) {}
-}
-pub trait InvokeOnError {
-    fn invoke_on_error(&self, _error: anyhow::Error) {}
-}
-impl InvokeOnStart for Empty {}
-impl InvokeOnEnd for Empty {}
-impl InvokeOnChat for Empty {}
-impl InvokeOnError for Empty {}
-impl<T> InvokeOnStart for T
-where
-    T: Fn(String),
-{
-    fn invoke_on_start(&self, live_id: String) {
-        (self)(live_id)
+    /// Whether the client is started or resumed and can `execute`.
+    pub(crate) fn is_started(&self) -> bool {
+        self.state.options.is_some()
     }
-}
-impl<T> InvokeOnEnd for T
-where
-    T: Fn(),
-{
-    fn invoke_on_end(&self) {
-        (self)()
+
+    /// Whether the last response had no continuation, e.g. because the stream ended.
+    pub fn has_ended(&self) -> bool {
+        self.state
+            .options
+            .as_ref()
+            .is_some_and(|options| options.continuation.is_empty())
     }
-}
-impl<T> InvokeOnChat for T
-where
-    T: Fn(ChatItem),
-{
-    fn invoke_on_chat(&self, chat_item: ChatItem) {
-        (self)(chat_item)
+
+    /// Calls `on_event`, the handler dedicated to the kind of `chat_event`, then `on_chat`
+    /// for chat items.
+    fn dispatch(&self, chat_event: ChatEvent) {
+        if EVF::IS_SET {
+            self.invoke_on_event(chat_event.clone());
+        }
+        match chat_event {
+            ChatEvent::Chat(chat_item) => self.invoke_on_chat(chat_item),
+            ChatEvent::SuperChat(chat_item) => {
+                self.invoke_on_superchat(chat_item.clone());
+                self.invoke_on_chat(chat_item);
+            }
+            ChatEvent::Sticker(chat_item) => {
+                self.invoke_on_sticker(chat_item.clone());
+                self.invoke_on_chat(chat_item);
+            }
+            ChatEvent::Membership(chat_item) => {
+                self.invoke_on_membership(chat_item.clone());
+                self.invoke_on_chat(chat_item);
+            }
+            ChatEvent::Gift(gift) => self.invoke_on_gift(gift),
+            ChatEvent::Ticker(ticker) => self.invoke_on_ticker(ticker),
+            ChatEvent::Banner(banner) => self.invoke_on_banner(banner),
+            ChatEvent::Raid(raid) => self.invoke_on_raid(raid),
+            ChatEvent::Poll(poll) => self.invoke_on_poll(poll),
+            ChatEvent::Engagement(engagement) => self.invoke_on_engagement(engagement),
+            ChatEvent::Unknown(unknown) => self.invoke_on_unknown(unknown),
+            ChatEvent::Deletion(deletion) => self.invoke_on_deletion(deletion),
+        }
     }
-}
-impl<T> InvokeOnError for T
-where
-    T: Fn(anyhow::Error),
-{
-    fn invoke_on_error(&self, error: anyhow::Error) {
-        (self)(error)
+
+    /// Returns the state needed to resume this client with
+    /// [`LiveChatClientBuilder::resume_from`], or `None` if it is not started.
+    pub fn checkpoint(&self) -> Option<Checkpoint> {
+        Some(Checkpoint {
+            live_url: self.live_url.clone(),
+            options: self.state.options.clone()?,
+            last_message_ids: self.state.dedup.ids(),
+            last_timestamp: self.state.last_timestamp,
+            pending: self.state.pending(),
+        })
     }
-}
-impl<SF, ENF, CF, ERF> InvokeOnStart for LiveChatClient<SF, ENF, CF, ERF>
-where
-    SF: InvokeOnStart,
-    ENF: InvokeOnEnd,
-    CF: InvokeOnChat,
-    ERF: InvokeOnError,
-{
-    fn invoke_on_start(&self, live_id: String) {
-        self.on_start.invoke_on_start(live_id)
+
+    /// Returns how many chat items were delivered and how many were dropped as duplicates.
+    pub fn dedup_stats(&self) -> DedupStats {
+        self.state.dedup.stats
     }
-}
-impl<SF, ENF, CF, ERF> InvokeOnEnd for LiveChatClient<SF, ENF, CF, ERF>
-where
-    SF: InvokeOnStart,
-    ENF: InvokeOnEnd,
-    CF: InvokeOnChat,
-    ERF: InvokeOnError,
-{
-    fn invoke_on_end(&self) {
-        self.on_end.invoke_on_end()
+
+    /// Returns the custom emojis seen in chat items since the client was built.
+    pub fn emoji_catalog(&self) -> &EmojiCatalog {
+        &self.state.emoji_catalog
     }
-}
-impl<SF, ENF, CF, ERF> InvokeOnChat for LiveChatClient<SF, ENF, CF, ERF>
-where
-    SF: InvokeOnStart,
-    ENF: InvokeOnEnd,
-    CF: InvokeOnChat,
-    ERF: InvokeOnError,
-{
-    fn invoke_on_chat(&self, chat_item: ChatItem) {
-        self.on_chat.invoke_on_chat(chat_item)
+
+    /// Returns the unknown renderers and fields seen so far, or `None` unless
+    /// enabled with [`LiveChatClientBuilder::schema_diagnostics`].
+    pub fn schema_drift_report(&self) -> Option<&SchemaDriftReport> {
+        self.state.drift.as_ref()
     }
-}
-impl<SF, ENF, CF, ERF> InvokeOnError for LiveChatClient<SF, ENF, CF, ERF>
-where
-    SF: InvokeOnStart,
-    ENF: InvokeOnEnd,
-    CF: InvokeOnChat,
-    ERF: InvokeOnError,
-{
-    fn invoke_on_error(&self, error: anyhow::Error) {
-        self.on_error.invoke_on_error(error)
+
+    /// Returns where the last response said to continue from, e.g. to wait for
+    /// [`Continuation::timeout`] before the next `execute`. `None` before the first
+    /// response or when the last one had no continuation, e.g. after the stream ended.
+    pub fn continuation(&self) -> Option<&Continuation> {
+        self.state.continuation.as_ref()
     }
 }
 
-pub struct LiveChatClientBuilder<U, SF, ENF, CF, ERF>
-where
+pub struct Empty;
+#[derive(TypestateBuilder)]
+#[typestate(client = LiveChatClient, empty = Empty)]
+pub struct LiveChatClientBuilder<
+    U,
+    SF,
+    ENF,
+    CF,
+    ERF,
+    SCF,
+    STF,
+    MF,
+    DF,
+    GF,
+    TF,
+    BF,
+    RDF,
+    PF,
+    EGF,
+    UF,
+    RAF,
+    EVF,
+> where
     SF: InvokeOnStart,
     ENF: InvokeOnEnd,
     CF: InvokeOnChat,
     ERF: InvokeOnError,
+    SCF: InvokeOnSuperchat,
+    STF: InvokeOnSticker,
+    MF: InvokeOnMembership,
+    DF: InvokeOnDeletion,
+    GF: InvokeOnGift,
+    TF: InvokeOnTicker,
+    BF: InvokeOnBanner,
+    RDF: InvokeOnRaid,
+    PF: InvokeOnPoll,
+    EGF: InvokeOnEngagement,
+    UF: InvokeOnUnknown,
+    RAF: InvokeOnRawAction,
+    EVF: InvokeOnEvent,
 {
+    #[typestate(ready = String)]
     live_url: U,
+    #[handler(live_id: String)]
     on_start: SF,
+    #[handler()]
     on_end: ENF,
+    #[handler(chat_item: ChatItem)]
     on_chat: CF,
+    #[handler(error: anyhow::Error)]
     on_error: ERF,
+    #[handler(chat_item: ChatItem)]
+    on_superchat: SCF,
+    #[handler(chat_item: ChatItem)]
+    on_sticker: STF,
+    #[handler(chat_item: ChatItem)]
+    on_membership: MF,
+    #[handler(deletion: Deletion)]
+    on_deletion: DF,
+    #[handler(gift: GiftMembership)]
+    on_gift: GF,
+    #[handler(ticker: Ticker)]
+    on_ticker: TF,
+    #[handler(banner: BannerEvent)]
+    on_banner: BF,
+    #[handler(raid: Raid)]
+    on_raid: RDF,
+    #[handler(poll: PollEvent)]
+    on_poll: PF,
+    #[handler(engagement: Engagement)]
+    on_engagement: EGF,
+    #[handler(unknown: UnknownAction)]
+    on_unknown: UF,
+    #[handler(action: serde_json::Value)]
+    on_raw_action: RAF,
+    #[handler(chat_event: ChatEvent)]
+    on_event: EVF,
+    state: ClientState,
 }
 
-impl LiveChatClientBuilder<(), Empty, Empty, Empty, Empty> {
+impl
+    LiveChatClientBuilder<
+        (),
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+    >
+{
     pub fn new() -> Self {
         Self {
             live_url: (),
@@ -181,139 +669,353 @@ impl LiveChatClientBuilder<(), Empty, Empty, Empty, Empty> {
             on_end: Empty {},
             on_chat: Empty {},
             on_error: Empty {},
+            on_superchat: Empty {},
+            on_sticker: Empty {},
+            on_membership: Empty {},
+            on_deletion: Empty {},
+            on_gift: Empty {},
+            on_ticker: Empty {},
+            on_banner: Empty {},
+            on_raid: Empty {},
+            on_poll: Empty {},
+            on_engagement: Empty {},
+            on_unknown: Empty {},
+            on_raw_action: Empty {},
+            on_event: Empty {},
+            state: ClientState::default(),
         }
     }
 }
 
-impl Default for LiveChatClientBuilder<(), Empty, Empty, Empty, Empty> {
+impl Default
+    for LiveChatClientBuilder<
+        (),
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+    >
+{
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<U, ENF, CF, ERF> LiveChatClientBuilder<U, Empty, ENF, CF, ERF>
+#[allow(clippy::type_complexity)]
+impl<SF, ENF, CF, ERF, SCF, STF, MF, DF, GF, TF, BF, RDF, PF, EGF, UF, RAF, EVF>
+    LiveChatClientBuilder<
+        (),
+        SF,
+        ENF,
+        CF,
+        ERF,
+        SCF,
+        STF,
+        MF,
+        DF,
+        GF,
+        TF,
+        BF,
+        RDF,
+        PF,
+        EGF,
+        UF,
+        RAF,
+        EVF,
+    >
 where
+    SF: InvokeOnStart,
     ENF: InvokeOnEnd,
     CF: InvokeOnChat,
     ERF: InvokeOnError,
+    SCF: InvokeOnSuperchat,
+    STF: InvokeOnSticker,
+    MF: InvokeOnMembership,
+    DF: InvokeOnDeletion,
+    GF: InvokeOnGift,
+    TF: InvokeOnTicker,
+    BF: InvokeOnBanner,
+    RDF: InvokeOnRaid,
+    PF: InvokeOnPoll,
+    EGF: InvokeOnEngagement,
+    UF: InvokeOnUnknown,
+    RAF: InvokeOnRawAction,
+    EVF: InvokeOnEvent,
 {
-    pub fn on_start<SF>(self, f: SF) -> LiveChatClientBuilder<U, SF, ENF, CF, ERF>
-    where
-        SF: Fn(String),
-    {
-        LiveChatClientBuilder {
-            live_url: self.live_url,
-            on_start: f,
-            on_end: self.on_end,
-            on_chat: self.on_chat,
-            on_error: self.on_error,
-        }
+    pub fn live_id(
+        self,
+        live_id: String,
+    ) -> LiveChatClientBuilder<
+        String,
+        SF,
+        ENF,
+        CF,
+        ERF,
+        SCF,
+        STF,
+        MF,
+        DF,
+        GF,
+        TF,
+        BF,
+        RDF,
+        PF,
+        EGF,
+        UF,
+        RAF,
+        EVF,
+    > {
+        self.with_live_url(format!("https://www.youtube.com/watch?v={}", live_id))
     }
-}
 
-impl<U, SF, CF, ERF> LiveChatClientBuilder<U, SF, Empty, CF, ERF>
-where
-    SF: InvokeOnStart,
-    CF: InvokeOnChat,
-    ERF: InvokeOnError,
-{
-    pub fn on_end<ENF>(self, f: ENF) -> LiveChatClientBuilder<U, SF, ENF, CF, ERF>
-    where
-        ENF: Fn(),
-    {
-        LiveChatClientBuilder {
-            live_url: self.live_url,
-            on_start: self.on_start,
-            on_end: f,
-            on_chat: self.on_chat,
-            on_error: self.on_error,
-        }
+    pub fn url(
+        self,
+        raw_url: impl AsRef<str>,
+    ) -> Result<
+        LiveChatClientBuilder<
+            String,
+            SF,
+            ENF,
+            CF,
+            ERF,
+            SCF,
+            STF,
+            MF,
+            DF,
+            GF,
+            TF,
+            BF,
+            RDF,
+            PF,
+            EGF,
+            UF,
+            RAF,
+            EVF,
+        >,
+        anyhow::Error,
+    > {
+        Url::parse(raw_url.as_ref())?;
+        Ok(self.with_live_url(raw_url.as_ref().to_string()))
     }
-}
 
-impl<U, SF, ENF, ERF> LiveChatClientBuilder<U, SF, ENF, Empty, ERF>
-where
-    SF: InvokeOnStart,
-    ENF: InvokeOnEnd,
-    ERF: InvokeOnError,
-{
-    pub fn on_chat<CF>(self, f: CF) -> LiveChatClientBuilder<U, SF, ENF, CF, ERF>
-    where
-        CF: Fn(ChatItem),
-    {
-        LiveChatClientBuilder {
-            live_url: self.live_url,
-            on_start: self.on_start,
-            on_end: self.on_end,
-            on_chat: f,
-            on_error: self.on_error,
-        }
+    pub fn channel_id(
+        self,
+        channel_id: String,
+    ) -> LiveChatClientBuilder<
+        String,
+        SF,
+        ENF,
+        CF,
+        ERF,
+        SCF,
+        STF,
+        MF,
+        DF,
+        GF,
+        TF,
+        BF,
+        RDF,
+        PF,
+        EGF,
+        UF,
+        RAF,
+        EVF,
+    > {
+        self.with_live_url(format!(
+            "https://www.youtube.com/channel/{}/live",
+            channel_id
+        ))
+    }
+
+    /// Restores a client from a [`Checkpoint`] so that `execute` continues
+    /// from the saved continuation without calling `start` again.
+    pub fn resume_from(
+        mut self,
+        checkpoint: Checkpoint,
+    ) -> LiveChatClientBuilder<
+        String,
+        SF,
+        ENF,
+        CF,
+        ERF,
+        SCF,
+        STF,
+        MF,
+        DF,
+        GF,
+        TF,
+        BF,
+        RDF,
+        PF,
+        EGF,
+        UF,
+        RAF,
+        EVF,
+    > {
+        let live_url = checkpoint.live_url.clone();
+        self.state.resume(checkpoint);
+        self.with_live_url(live_url)
     }
 }
 
-impl<U, SF, ENF, CF> LiveChatClientBuilder<U, SF, ENF, CF, Empty>
+impl<U, SF, ENF, CF, ERF, SCF, STF, MF, DF, GF, TF, BF, RDF, PF, EGF, UF, RAF, EVF>
+    LiveChatClientBuilder<
+        U,
+        SF,
+        ENF,
+        CF,
+        ERF,
+        SCF,
+        STF,
+        MF,
+        DF,
+        GF,
+        TF,
+        BF,
+        RDF,
+        PF,
+        EGF,
+        UF,
+        RAF,
+        EVF,
+    >
 where
     SF: InvokeOnStart,
     ENF: InvokeOnEnd,
     CF: InvokeOnChat,
+    ERF: InvokeOnError,
+    SCF: InvokeOnSuperchat,
+    STF: InvokeOnSticker,
+    MF: InvokeOnMembership,
+    DF: InvokeOnDeletion,
+    GF: InvokeOnGift,
+    TF: InvokeOnTicker,
+    BF: InvokeOnBanner,
+    RDF: InvokeOnRaid,
+    PF: InvokeOnPoll,
+    EGF: InvokeOnEngagement,
+    UF: InvokeOnUnknown,
+    RAF: InvokeOnRawAction,
+    EVF: InvokeOnEvent,
 {
-    pub fn on_error<ERF>(self, f: ERF) -> LiveChatClientBuilder<U, SF, ENF, CF, ERF>
+    /// Sets how many recent message ids are remembered to suppress duplicated items.
+    /// `0` disables de-duplication. Defaults to [`DEFAULT_DEDUP_CAPACITY`].
+    pub fn dedup_capacity(mut self, capacity: usize) -> Self {
+        self.state.dedup.set_capacity(capacity);
+        self
+    }
+
+    /// Holds each chat item for `delay` after its timestamp and delivers items
+    /// to `on_chat` in timestamp order instead of the order YouTube sent them.
+    pub fn reorder_delay(mut self, delay: Duration) -> Self {
+        self.state.reorder = Some(ReorderBuffer::new(delay));
+        self
+    }
+
+    /// Parses each action on its own and passes the ones that fail to `on_unknown`
+    /// instead of failing the whole batch with `on_error`.
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.state.parse_options.lenient = lenient;
+        self
+    }
+
+    /// Parses the renderer named `name` in `addChatItemAction.item` with `R`, see
+    /// [`RendererRegistry::register`](crate::parser::RendererRegistry::register).
+    pub fn renderer<R>(
+        mut self,
+        name: impl Into<String>,
+        to_event: fn(ChatItem) -> ChatEvent,
+    ) -> Self
     where
-        ERF: Fn(anyhow::Error),
+        R: ChatRenderer + DeserializeOwned + 'static,
     {
-        LiveChatClientBuilder {
-            live_url: self.live_url,
-            on_start: self.on_start,
-            on_end: self.on_end,
-            on_chat: self.on_chat,
-            on_error: f,
-        }
+        self.state
+            .parse_options
+            .renderers
+            .register::<R>(name, to_event);
+        self
     }
-}
 
-impl<SF, ENF, CF, ERF> LiveChatClientBuilder<(), SF, ENF, CF, ERF>
-where
-    SF: InvokeOnStart,
-    ENF: InvokeOnEnd,
-    CF: InvokeOnChat,
-    ERF: InvokeOnError,
-{
-    pub fn live_id(self, live_id: String) -> LiveChatClientBuilder<String, SF, ENF, CF, ERF> {
-        LiveChatClientBuilder {
-            live_url: format!("https://www.youtube.com/watch?v={}", live_id),
-            on_start: self.on_start,
-            on_end: self.on_end,
-            on_chat: self.on_chat,
-            on_error: self.on_error,
-        }
+    /// Records JSON fields and renderers that the parser does not know, see
+    /// [`LiveChatClient::schema_drift_report`]. Costs an extra pass over each response.
+    pub fn schema_diagnostics(mut self, enabled: bool) -> Self {
+        self.state.drift = enabled.then(SchemaDriftReport::new);
+        self
     }
 
-    pub fn url(
-        self,
-        raw_url: impl AsRef<str>,
-    ) -> Result<LiveChatClientBuilder<String, SF, ENF, CF, ERF>, anyhow::Error> {
-        Url::parse(raw_url.as_ref())?;
-        Ok(LiveChatClientBuilder {
-            live_url: raw_url.as_ref().to_string(),
-            on_start: self.on_start,
-            on_end: self.on_end,
-            on_chat: self.on_chat,
-            on_error: self.on_error,
-        })
+    /// Sends every request of this client through `proxy`, e.g. `http://host:8080`
+    /// or `socks5://host:1080`.
+    pub fn proxy(self, proxy: impl Into<String>) -> Self {
+        self.proxy_rotation(Arc::new(ProxyRotation::new(vec![proxy.into()])))
+    }
+
+    /// Takes a proxy from `rotation`, which can be shared with other clients, and
+    /// moves to another one of it after repeated failed requests.
+    pub fn proxy_rotation(mut self, rotation: Arc<ProxyRotation>) -> Self {
+        self.state.proxy = Some(ProxySession::new(rotation));
+        self
+    }
+
+    /// Sets the language (`hl`) of localized strings, e.g. `en` or `ja`; also sent
+    /// as `Accept-Language`. Without it, `hl` is [`DEFAULT_LANGUAGE`](crate::request::DEFAULT_LANGUAGE). In other languages
+    /// membership upgrades are reported as [`MembershipKind::New`](crate::item::MembershipKind::New).
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.state.context.language = Some(language.into());
+        self
+    }
+
+    /// Sets the region (`gl`), e.g. `US` or `JP`.
+    pub fn region(mut self, region: impl Into<String>) -> Self {
+        self.state.context.region = Some(region.into());
+        self
     }
 
-    pub fn channel_id(self, channel_id: String) -> LiveChatClientBuilder<String, SF, ENF, CF, ERF> {
-        LiveChatClientBuilder {
-            live_url: format!("https://www.youtube.com/channel/{}/live", channel_id),
-            on_start: self.on_start,
-            on_end: self.on_end,
-            on_chat: self.on_chat,
-            on_error: self.on_error,
+    /// Sets the IANA time zone, e.g. `Asia/Tokyo`.
+    pub fn time_zone(mut self, time_zone: impl Into<String>) -> Self {
+        self.state.context.time_zone = Some(time_zone.into());
+        self
+    }
+
+    /// Sets the `User-Agent` header, also sent as `userAgent` in the request body.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.state.context.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Sends a cookie with every request.
+    pub fn cookie(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.state.context.cookies.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sends [`CONSENT_COOKIES`] so that `start` gets the live page instead of the
+    /// cookie consent page, e.g. from servers in the EEA.
+    pub fn consent_cookies(mut self) -> Self {
+        for (name, value) in CONSENT_COOKIES {
+            self = self.cookie(*name, *value);
         }
+        self
     }
-}
 
-youtube_chat_macro::gen_builder!();
+    /// Adds a header to every request.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.state.context.headers.push((name.into(), value.into()));
+        self
+    }
+}
 
 #[cfg(test)]
 mod live_chat_tests {
@@ -361,4 +1063,245 @@ mod live_chat_tests {
             "https://www.youtube.com/watch?v=Dx5qFachd3A"
         );
     }
+
+    fn chat_event(id: &str, timestamp_usec: i64) -> ChatEvent {
+        use crate::item::Author;
+        use chrono::TimeZone;
+        ChatEvent::Chat(ChatItem {
+            id: id.to_string(),
+            author: Author {
+                name: None,
+                thumbnail: None,
+                channel_id: "UCxxxxxxxxxxxxxxxxxxxxxx".to_string(),
+                badge: None,
+            },
+            message: Vec::new(),
+            superchat: None,
+            membership: None,
+            is_membership: false,
+            is_verified: false,
+            is_owner: false,
+            is_moderator: false,
+            timestamp: Some(Utc.timestamp_nanos(timestamp_usec * 1000)),
+        })
+    }
+
+    #[test]
+    fn test_resume_from_checkpoint() {
+        let checkpoint = Checkpoint {
+            live_url: "https://www.youtube.com/watch?v=Dx5qFachd3A".to_string(),
+            options: RequestOptions {
+                api_key: "key".to_string(),
+                client_version: "2.20230208.00.00".to_string(),
+                continuation: "continuation".to_string(),
+            },
+            last_message_ids: vec!["a".to_string()],
+            last_timestamp: None,
+            pending: Vec::new(),
+        };
+        let json = serde_json::to_string(&checkpoint).unwrap();
+        let checkpoint: Checkpoint = serde_json::from_str(&json).unwrap();
+
+        let mut client = LiveChatClientBuilder::new()
+            .resume_from(checkpoint)
+            .on_chat(|_chat_item| {})
+            .build();
+        assert_eq!(
+            &client.live_url,
+            "https://www.youtube.com/watch?v=Dx5qFachd3A"
+        );
+        let unseen = client
+            .state
+            .take_unseen(vec![chat_event("a", 1), chat_event("b", 2)]);
+        assert_eq!(unseen.len(), 1);
+        assert_eq!(unseen[0].chat_item().unwrap().id, "b");
+
+        let checkpoint = client.checkpoint().unwrap();
+        assert_eq!(checkpoint.options.continuation, "continuation");
+        assert_eq!(checkpoint.last_message_ids, vec!["a", "b"]);
+        assert_eq!(checkpoint.last_timestamp.unwrap().timestamp_micros(), 2);
+    }
+
+    #[test]
+    fn test_poll_delay() {
+        let mut client = LiveChatClientBuilder::new()
+            .live_id("_eM9C3zZL14".to_string())
+            .build();
+        let interval = Duration::from_secs(5);
+        assert_eq!(client.poll_delay(interval), interval);
+        client.state.continuation = Some(Continuation::Timed {
+            continuation: "next".to_string(),
+            timeout: Duration::from_secs(2),
+        });
+        assert_eq!(client.poll_delay(interval), Duration::from_secs(2));
+        client.state.continuation = Some(Continuation::Replay {
+            continuation: "next".to_string(),
+            time_until_last_message: None,
+        });
+        assert_eq!(client.poll_delay(interval), interval);
+    }
+
+    #[test]
+    fn test_dedup_window() {
+        let mut client = LiveChatClientBuilder::new()
+            .live_id("_eM9C3zZL14".to_string())
+            .dedup_capacity(2)
+            .build();
+        let delivered = client.state.take_unseen(vec![
+            chat_event("a", 1),
+            chat_event("b", 2),
+            chat_event("a", 1),
+        ]);
+        assert_eq!(delivered.len(), 2);
+        let delivered = client
+            .state
+            .take_unseen(vec![chat_event("b", 2), chat_event("c", 3)]);
+        assert_eq!(delivered.len(), 1);
+        // "a" was evicted when "c" entered the window
+        let delivered = client.state.take_unseen(vec![chat_event("a", 1)]);
+        assert_eq!(delivered.len(), 1);
+        assert_eq!(
+            client.dedup_stats(),
+            DedupStats {
+                delivered: 4,
+                suppressed: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_dedup_stats() {
+        use crate::item::{Author, Ticker, TickerKind};
+        let mut client = LiveChatClientBuilder::new()
+            .live_id("_eM9C3zZL14".to_string())
+            .build();
+        let deletion = ChatEvent::Deletion(Deletion::Item {
+            id: "a".to_string(),
+        });
+        let ticker = ChatEvent::Ticker(Ticker {
+            id: "t".to_string(),
+            kind: TickerKind::PaidMessage,
+            author: Author {
+                name: None,
+                thumbnail: None,
+                channel_id: "UCxxxxxxxxxxxxxxxxxxxxxx".to_string(),
+                badge: None,
+            },
+            amount: Some("¥500".to_string()),
+            detail: None,
+            sticker: None,
+            duration: Duration::from_secs(60),
+            full_duration: Duration::from_secs(60),
+            chat_item_id: Some("b".to_string()),
+        });
+        let delivered = client.state.accept(
+            vec![
+                chat_event("a", 1),
+                deletion.clone(),
+                chat_event("b", 2),
+                ticker.clone(),
+            ],
+            Utc::now(),
+        );
+        assert_eq!(delivered.len(), 4);
+        let delivered = client.state.accept(
+            vec![chat_event("b", 2), chat_event("a", 1), deletion, ticker],
+            Utc::now(),
+        );
+        assert_eq!(delivered.len(), 1);
+        assert_eq!(
+            client.dedup_stats(),
+            DedupStats {
+                delivered: 3,
+                suppressed: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_reorder_buffer() {
+        use chrono::TimeZone;
+        let mut client = LiveChatClientBuilder::new()
+            .live_id("_eM9C3zZL14".to_string())
+            .reorder_delay(Duration::from_secs(2))
+            .build();
+        let at = |sec: i64| Utc.timestamp_nanos(sec * 1_000_000_000);
+        let ids = |chat_events: Vec<ChatEvent>| {
+            chat_events
+                .into_iter()
+                .filter_map(|chat_event| Some(chat_event.into_chat_item()?.id))
+                .collect::<Vec<_>>()
+        };
+
+        let ready = client.state.accept(
+            vec![
+                chat_event("c", 3_000_000),
+                chat_event("a", 1_000_000),
+                chat_event("b", 2_000_000),
+            ],
+            at(4),
+        );
+        assert_eq!(ids(ready), vec!["a", "b"]);
+        assert_eq!(ids(client.state.pending()), vec!["c"]);
+
+        let ready = client.state.accept(vec![chat_event("d", 2_500_000)], at(6));
+        assert_eq!(ids(ready), vec!["d", "c"]);
+    }
+
+    #[test]
+    fn test_dispatch() {
+        use std::cell::RefCell;
+        let chats = RefCell::new(Vec::new());
+        let superchats = RefCell::new(Vec::new());
+        let deletions = RefCell::new(Vec::new());
+        let events = RefCell::new(0);
+        let client = LiveChatClientBuilder::new()
+            .live_id("_eM9C3zZL14".to_string())
+            .on_chat(|chat_item| chats.borrow_mut().push(chat_item.id))
+            .on_superchat(|chat_item| superchats.borrow_mut().push(chat_item.id))
+            .on_deletion(|deletion| deletions.borrow_mut().push(deletion))
+            .on_event(|_chat_event| *events.borrow_mut() += 1)
+            .build();
+        let superchat = chat_event("b", 2).into_chat_item().unwrap();
+        client.dispatch(chat_event("a", 1));
+        client.dispatch(ChatEvent::SuperChat(superchat));
+        client.dispatch(ChatEvent::Deletion(Deletion::Item {
+            id: "a".to_string(),
+        }));
+        assert_eq!(*chats.borrow(), vec!["a", "b"]);
+        assert_eq!(*superchats.borrow(), vec!["b"]);
+        assert!(matches!(&deletions.borrow()[..], [Deletion::Item { id }] if id == "a"));
+        assert_eq!(*events.borrow(), 3);
+    }
+
+    #[test]
+    fn test_continue_past_malformed_page() {
+        use std::cell::RefCell;
+        let errors = RefCell::new(0);
+        let mut client = LiveChatClientBuilder::new()
+            .live_id("_eM9C3zZL14".to_string())
+            .on_error(|_err| *errors.borrow_mut() += 1)
+            .build();
+        let options = RequestOptions {
+            api_key: "key".to_string(),
+            client_version: "2.20230208.00.00".to_string(),
+            continuation: "first".to_string(),
+        };
+        client.started(options.clone(), "_eM9C3zZL14".to_string());
+        let body = serde_json::json!({
+            "responseContext": {},
+            "continuationContents": { "liveChatContinuation": {
+                "continuations": [{
+                    "timedContinuationData": { "timeoutMs": 5000, "continuation": "next" }
+                }],
+                "actions": [{ "addChatItemAction": { "item": {
+                    "liveChatTextMessageRenderer": { "message": { "runs": [] } }
+                } } }]
+            } }
+        });
+        client.handle_get_live_chat(options, Ok(body.to_string()));
+        assert_eq!(*errors.borrow(), 1);
+        assert_eq!(client.state.options.as_ref().unwrap().continuation, "next");
+        assert_eq!(client.continuation().unwrap().token(), "next");
+    }
 }
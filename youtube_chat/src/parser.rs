@@ -1,15 +1,61 @@
 use crate::{
-    item::{Author, Badge, ChatItem, EmojiItem, ImageItem, MessageItem, SuperChat},
+    diagnostics::SchemaDriftReport,
+    item::{
+        Author, Badge, Banner, BannerEvent, ChatEvent, ChatItem, Continuation, Deletion, EmojiItem,
+        Engagement, GiftMembership, GiftPurchase, GiftRedemption, ImageItem, ImageSize, Membership,
+        MembershipKind, MessageItem, Poll, PollChoice, PollEvent, Raid, SuperChat, TextStyle,
+        Ticker, TickerKind, UnknownAction,
+    },
     request::RequestOptions,
     youtube_types::{
-        Action, AuthorBadge, GetLiveChatResponse, LiveChatMembershipItemRenderer,
-        LiveChatPaidMessageRenderer, LiveChatPaidStickerRenderer, LiveChatTextMessageRenderer,
-        MessageRun, Thumbnail,
+        Action, ActionItem, AuthorBadge, Continuation as ContinuationData, CustomThumbnail,
+        GetLiveChatResponse, HeaderSubText, LiveChatBannerRedirectRenderer, LiveChatBannerRenderer,
+        LiveChatMembershipItemRenderer, LiveChatPaidMessageRenderer, LiveChatPaidStickerRenderer,
+        LiveChatSponsorshipsGiftPurchaseAnnouncementRenderer,
+        LiveChatSponsorshipsGiftRedemptionAnnouncementRenderer, LiveChatTextMessageRenderer,
+        Message, MessageRun, NavigationEndpoint, PollRenderer, Thumbnail, TickerItem,
     },
 };
+use std::{borrow::Cow, collections::HashMap, fmt, time::Duration};
+
 use anyhow::anyhow;
 use chrono::{DateTime, TimeZone, Utc};
 use regex::Regex;
+use serde::{
+    de::{DeserializeOwned, IgnoredAny},
+    Deserialize,
+};
+use serde_json::value::RawValue;
+use url::Url;
+
+/// A page YouTube shows instead of the live page. Returned by [`get_options_from_live_page`]
+/// inside `anyhow::Error`; tell them apart with `downcast_ref::<LivePageError>()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LivePageError {
+    /// The cookie consent page shown in the EEA and the UK. Pre-set consent cookies
+    /// skip it, see [`LiveChatClientBuilder::consent_cookies`](crate::live_chat::LiveChatClientBuilder::consent_cookies).
+    ConsentRequired,
+    /// A "confirm you're not a bot" or unusual traffic page.
+    BotCheck,
+}
+
+impl fmt::Display for LivePageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LivePageError::ConsentRequired => {
+                write!(
+                    f,
+                    "YouTube showed the cookie consent page instead of the live page."
+                )
+            }
+            LivePageError::BotCheck => {
+                write!(f, "YouTube asked to confirm that this client is not a bot.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LivePageError {}
 
 pub fn get_options_from_live_page(data: String) -> Result<(RequestOptions, String), anyhow::Error> {
     let live_id_regex =
@@ -20,7 +66,12 @@ pub fn get_options_from_live_page(data: String) -> Result<(RequestOptions, Strin
         .and_then(|captures| captures.get(1))
     {
         Some(matched) => matched.as_str().to_string(),
-        None => return Err(anyhow!("Live Stream was not found.")),
+        None => {
+            return Err(live_page_error(
+                &data,
+                anyhow!("Live Stream was not found."),
+            ))
+        }
     };
 
     let replay_regex = Regex::new(r#"['"]isReplay['"]:\s*(true)"#).unwrap();
@@ -34,7 +85,12 @@ pub fn get_options_from_live_page(data: String) -> Result<(RequestOptions, Strin
         .and_then(|captures| captures.get(1))
     {
         Some(matched) => matched.as_str().to_string(),
-        None => return Err(anyhow!("{live_id} is finished live.")),
+        None => {
+            return Err(live_page_error(
+                &data,
+                anyhow!("{live_id} is finished live."),
+            ))
+        }
     };
 
     let client_version_regex = Regex::new(r#"['"]clientVersion['"]:\s*['"]([\d.]+?)['"]"#).unwrap();
@@ -43,7 +99,12 @@ pub fn get_options_from_live_page(data: String) -> Result<(RequestOptions, Strin
         .and_then(|captures| captures.get(1))
     {
         Some(matched) => matched.as_str().to_string(),
-        None => return Err(anyhow!("Client Version was not found.")),
+        None => {
+            return Err(live_page_error(
+                &data,
+                anyhow!("Client Version was not found."),
+            ))
+        }
     };
 
     let continuation_regex = Regex::new(r#"['"]continuation['"]:\s*['"](.+?)['"]"#).unwrap();
@@ -52,7 +113,12 @@ pub fn get_options_from_live_page(data: String) -> Result<(RequestOptions, Strin
         .and_then(|captures| captures.get(1))
     {
         Some(matched) => matched.as_str().to_string(),
-        None => return Err(anyhow!("Client Version was not found.")),
+        None => {
+            return Err(live_page_error(
+                &data,
+                anyhow!("Client Version was not found."),
+            ))
+        }
     };
 
     Ok((
@@ -65,8 +131,39 @@ pub fn get_options_from_live_page(data: String) -> Result<(RequestOptions, Strin
     ))
 }
 
+/// `err`, or [`LivePageError`] if `data` is a bot-check or consent page instead of
+/// the live page. Only checked once reading the live page failed, since a live page
+/// may quote these phrases, e.g. in its title or description.
+fn live_page_error(data: &str, err: anyhow::Error) -> anyhow::Error {
+    let bot_check_regex = Regex::new(
+        r#"confirm (?:you|that you)(?:'|’|\\u2019)re not a bot|unusual traffic from your computer network"#,
+    )
+    .unwrap();
+    if bot_check_regex.is_match(data) {
+        LivePageError::BotCheck.into()
+    } else if data.contains("consent.youtube.com") {
+        LivePageError::ConsentRequired.into()
+    } else {
+        err
+    }
+}
+
 pub fn parse_chat_data(data: GetLiveChatResponse) -> (Vec<ChatItem>, String) {
-    let chat_items = if !data
+    let (chat_events, continuation) = parse_chat_events(data);
+    let chat_items = chat_events
+        .into_iter()
+        .filter_map(ChatEvent::into_chat_item)
+        .collect();
+    let continuation = continuation
+        .map(Continuation::into_token)
+        .unwrap_or_default();
+    (chat_items, continuation)
+}
+
+/// Parses the actions of a response together with where to continue from, or
+/// `None` if the response has no continuation, e.g. when the stream ended.
+pub fn parse_chat_events(data: GetLiveChatResponse) -> (Vec<ChatEvent>, Option<Continuation>) {
+    let chat_events = if !data
         .continuation_contents
         .live_chat_continuaton
         .actions
@@ -79,277 +176,800 @@ pub fn parse_chat_data(data: GetLiveChatResponse) -> (Vec<ChatItem>, String) {
             .actions
             .unwrap()
             .into_iter()
-            .filter_map(parse_action_to_chat_item)
+            .filter_map(parse_action_to_event)
             .collect()
     } else {
         Vec::new()
     };
-    let continuation_data = data
-        .continuation_contents
-        .live_chat_continuaton
-        .continuations
-        .into_iter()
-        .next();
-    let continuation = {
-        if let Some(continuation_data) = continuation_data {
-            if let Some(invalidation_continuation_data) =
-                continuation_data.invalidation_continuation_data
-            {
-                invalidation_continuation_data.continuation
-            } else if let Some(timed_continuation_data) = continuation_data.timed_continuation_data
-            {
-                timed_continuation_data.continuation
-            } else {
-                String::new()
-            }
+    let continuation = parse_continuation(
+        data.continuation_contents
+            .live_chat_continuaton
+            .continuations,
+    );
+    (chat_events, continuation)
+}
+
+/// Reads only the continuation of a `get_live_chat` response body, e.g. to move past
+/// a response whose actions fail to parse.
+pub fn parse_continuation_from_slice(data: &[u8]) -> Option<Continuation> {
+    let response: GetLiveChatResponse<IgnoredAny> = serde_json::from_slice(data).ok()?;
+    parse_continuation(
+        response
+            .continuation_contents
+            .live_chat_continuaton
+            .continuations,
+    )
+}
+
+/// Returns the first continuation of a kind this crate knows.
+fn parse_continuation(continuations: Vec<ContinuationData>) -> Option<Continuation> {
+    continuations.into_iter().find_map(|data| {
+        if let Some(data) = data.invalidation_continuation_data {
+            Some(Continuation::Invalidation {
+                continuation: data.continuation,
+                timeout: Duration::from_millis(data.timeout_ms as u64),
+                topic: data.invalidation_id.topic,
+            })
+        } else if let Some(data) = data.timed_continuation_data {
+            Some(Continuation::Timed {
+                continuation: data.continuation,
+                timeout: Duration::from_millis(data.timeout_ms as u64),
+            })
+        } else if let Some(data) = data.reload_continuation_data {
+            Some(Continuation::Reload {
+                continuation: data.continuation,
+            })
+        } else if let Some(data) = data.live_chat_replay_continuation_data {
+            Some(Continuation::Replay {
+                continuation: data.continuation,
+                time_until_last_message: data
+                    .time_until_last_message_msec
+                    .map(Duration::from_millis),
+            })
         } else {
-            String::new()
+            Some(Continuation::PlayerSeek {
+                continuation: data.player_seek_continuation_data?.continuation,
+            })
         }
+    })
+}
+
+/// Options of [`parse_chat_events_with`].
+#[derive(Default)]
+pub struct ParseOptions {
+    /// Return malformed or unsupported actions as [`ChatEvent::Unknown`] instead of
+    /// failing the whole batch or dropping them.
+    pub lenient: bool,
+    pub renderers: RendererRegistry,
+}
+
+/// Like [`parse_chat_events`], but a malformed or unsupported action becomes
+/// [`ChatEvent::Unknown`] instead of failing the whole batch or being dropped.
+pub fn parse_chat_events_lenient(
+    data: serde_json::Value,
+) -> Result<(Vec<ChatEvent>, Option<Continuation>), serde_json::Error> {
+    let options = ParseOptions {
+        lenient: true,
+        ..Default::default()
     };
-    (chat_items, continuation)
+    parse_chat_events_with(data, &options)
 }
 
-fn parse_action_to_chat_item(action: Action) -> Option<ChatItem> {
-    let message_renderer = renderer_from_action(action)?;
-    let message = message_renderer.runs();
-    let author_name_text = message_renderer.author_name();
-    let id = message_renderer.id();
-    let thumbnail =
-        parse_thumbnails_to_image_item(message_renderer.thumbnails(), author_name_text.clone());
-    let channel_id = message_renderer.channel_id();
-    let message = parse_message(message);
-    let timestamp = message_renderer.time_stamp();
-    let superchat = message_renderer.superchat();
-    let mut chat_item = ChatItem {
-        id,
-        author: Author {
-            name: author_name_text,
-            thumbnail,
-            channel_id,
-            badge: None,
-        },
-        message,
-        superchat,
-        is_membership: false,
-        is_verified: false,
-        is_owner: false,
-        is_moderator: false,
-        timestamp,
+/// Parses a `get_live_chat` response action by action, trying the renderers
+/// registered in `options` before the built-in ones.
+pub fn parse_chat_events_with(
+    data: serde_json::Value,
+    options: &ParseOptions,
+) -> Result<(Vec<ChatEvent>, Option<Continuation>), serde_json::Error> {
+    let data: GetLiveChatResponse<serde_json::Value> = serde_json::from_value(data)?;
+    let live_chat_continuation = data.continuation_contents.live_chat_continuaton;
+    let actions = live_chat_continuation.actions.unwrap_or_default();
+    let chat_events = parse_actions(actions.iter().map(ActionJson::Value), options, None)?;
+    let continuation = parse_continuation(live_chat_continuation.continuations);
+    Ok((chat_events, continuation))
+}
+
+/// Like [`parse_chat_events_with`], but reads the response body directly without
+/// building a JSON tree of it. Each action is deserialized from its own slice of
+/// `data`; the slices are returned too for callers that want the raw JSON.
+/// The chat events still own copies of their strings.
+#[allow(clippy::type_complexity)]
+pub fn parse_chat_events_from_slice<'a>(
+    data: &'a [u8],
+    options: &ParseOptions,
+) -> Result<(Vec<ChatEvent>, Vec<&'a RawValue>, Option<Continuation>), serde_json::Error> {
+    parse_slice(data, options, None)
+}
+
+/// [`parse_chat_events_from_slice`] that also records what `drift` does not model
+/// while deserializing, instead of parsing the body a second time.
+#[allow(clippy::type_complexity)]
+pub(crate) fn parse_slice<'a>(
+    data: &'a [u8],
+    options: &ParseOptions,
+    mut drift: Option<&mut SchemaDriftReport>,
+) -> Result<(Vec<ChatEvent>, Vec<&'a RawValue>, Option<Continuation>), serde_json::Error> {
+    let data: GetLiveChatResponse<&RawValue> = match drift.as_deref_mut() {
+        Some(drift) => drift.record_response(data)?,
+        None => serde_json::from_slice(data)?,
     };
-    message_renderer.process_badge(&mut chat_item);
-    Some(chat_item)
+    let live_chat_continuation = data.continuation_contents.live_chat_continuaton;
+    let raw_actions = live_chat_continuation.actions.unwrap_or_default();
+    let chat_events = parse_actions(
+        raw_actions.iter().copied().map(ActionJson::Raw),
+        options,
+        drift,
+    )?;
+    let continuation = parse_continuation(live_chat_continuation.continuations);
+    Ok((chat_events, raw_actions, continuation))
 }
 
-pub enum Renderer {
-    LiveChatTextMessageRenderer(LiveChatTextMessageRenderer),
-    LiveChatPaidMessageRenderer(LiveChatPaidMessageRenderer),
-    LiveChatMembershipItemRenderer(LiveChatMembershipItemRenderer),
-    LiveChatPaidStickerRenderer(LiveChatPaidStickerRenderer),
+/// An action or renderer, either a slice of the response body or a node of a `Value` tree.
+#[derive(Clone, Copy)]
+enum ActionJson<'a> {
+    Raw(&'a RawValue),
+    Value(&'a serde_json::Value),
 }
-impl Renderer {
-    fn runs(&self) -> Vec<MessageRun> {
-        match self {
-            Renderer::LiveChatTextMessageRenderer(renderer) => renderer.message.runs.clone(),
-            Renderer::LiveChatPaidMessageRenderer(renderer) => renderer
-                .live_chat_text_message_renderer
-                .message
-                .runs
-                .clone(),
-            Renderer::LiveChatMembershipItemRenderer(renderer) => renderer
-                .header_sub_text
-                .as_ref()
-                .map(|header_sub_text| header_sub_text.runs.clone())
-                .unwrap_or(Vec::new()),
-            Renderer::LiveChatPaidStickerRenderer(_) => Vec::new(),
-        }
-    }
 
-    fn author_name(&self) -> Option<String> {
+impl ActionJson<'_> {
+    fn deserialize<T: DeserializeOwned>(self) -> Result<T, serde_json::Error> {
         match self {
-            Renderer::LiveChatTextMessageRenderer(renderer) => renderer
-                .message_renderer_base
-                .author_name
-                .clone()
-                .map(|name| name.simple_text),
-            Renderer::LiveChatPaidMessageRenderer(renderer) => renderer
-                .live_chat_text_message_renderer
-                .message_renderer_base
-                .author_name
-                .clone()
-                .map(|name| name.simple_text),
-            Renderer::LiveChatMembershipItemRenderer(renderer) => renderer
-                .message_renderer_base
-                .author_name
-                .clone()
-                .map(|name| name.simple_text),
-            Renderer::LiveChatPaidStickerRenderer(renderer) => renderer
-                .message_renderer_base
-                .author_name
-                .clone()
-                .map(|name| name.simple_text),
+            ActionJson::Raw(raw) => serde_json::from_str(raw.get()),
+            ActionJson::Value(value) => T::deserialize(value),
         }
     }
+}
 
-    fn id(&self) -> String {
-        match self {
-            Renderer::LiveChatTextMessageRenderer(renderer) => {
-                renderer.message_renderer_base.id.clone()
-            }
-            Renderer::LiveChatPaidMessageRenderer(renderer) => renderer
-                .live_chat_text_message_renderer
-                .message_renderer_base
-                .id
-                .clone(),
-            Renderer::LiveChatMembershipItemRenderer(renderer) => {
-                renderer.message_renderer_base.id.clone()
+fn parse_actions<'a>(
+    actions: impl ExactSizeIterator<Item = ActionJson<'a>>,
+    options: &ParseOptions,
+    mut drift: Option<&mut SchemaDriftReport>,
+) -> Result<Vec<ChatEvent>, serde_json::Error> {
+    let mut chat_events = Vec::with_capacity(actions.len());
+    for action in actions {
+        let typed = match (drift.as_deref_mut(), action) {
+            (Some(drift), ActionJson::Raw(raw_action)) => Some(drift.record_action(raw_action)),
+            _ => None,
+        };
+        let error = match parse_action(action, typed, &options.renderers) {
+            Ok(Some(chat_event)) => {
+                chat_events.push(chat_event);
+                continue;
             }
-            Renderer::LiveChatPaidStickerRenderer(renderer) => {
-                renderer.message_renderer_base.id.clone()
+            Ok(None) if !options.lenient => continue,
+            Err(err) if !options.lenient => return Err(err),
+            Ok(None) => None,
+            Err(err) => Some(err),
+        };
+        let action: serde_json::Value = action.deserialize()?;
+        let reason = match error {
+            None => format!("unsupported {}", describe_action(&action)),
+            Some(err) => format!("malformed {}: {}", describe_action(&action), err),
+        };
+        chat_events.push(ChatEvent::Unknown(UnknownAction { action, reason }));
+    }
+    Ok(chat_events)
+}
+
+/// `typed` is `action` already deserialized, if it was.
+fn parse_action(
+    action: ActionJson,
+    typed: Option<Result<Action, serde_json::Error>>,
+    renderers: &RendererRegistry,
+) -> Result<Option<ChatEvent>, serde_json::Error> {
+    if let Some(chat_event) = renderers.parse(action) {
+        return chat_event.map(Some);
+    }
+    let action = match typed {
+        Some(action) => action?,
+        None => action.deserialize()?,
+    };
+    Ok(parse_action_to_event(action))
+}
+
+type ParseRenderer = dyn Fn(ActionJson) -> Result<ChatEvent, serde_json::Error> + Send + Sync;
+
+/// Renderers added by users, keyed by their name in `addChatItemAction.item`.
+#[derive(Default)]
+pub struct RendererRegistry {
+    renderers: HashMap<String, Box<ParseRenderer>>,
+}
+
+/// Just enough of an action to find a registered renderer in it.
+#[derive(Deserialize)]
+struct RegisteredAction<'a> {
+    #[serde(rename = "addChatItemAction", borrow)]
+    add_chat_item_action: Option<RegisteredItem<'a>>,
+}
+
+#[derive(Deserialize)]
+struct RegisteredItem<'a> {
+    #[serde(borrow)]
+    item: HashMap<Cow<'a, str>, &'a RawValue>,
+}
+
+impl RendererRegistry {
+    /// Parses the renderer named `name`, e.g. `"liveChatFooRenderer"`, as `R` and
+    /// wraps the chat item with `to_event`, e.g. `ChatEvent::Chat`.
+    /// A registered name takes precedence over the built-in renderers.
+    pub fn register<R>(&mut self, name: impl Into<String>, to_event: fn(ChatItem) -> ChatEvent)
+    where
+        R: ChatRenderer + DeserializeOwned + 'static,
+    {
+        self.renderers.insert(
+            name.into(),
+            Box::new(move |renderer| {
+                let renderer: R = renderer.deserialize()?;
+                Ok(to_event(parse_renderer_to_chat_item(&renderer)))
+            }),
+        );
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.renderers.is_empty()
+    }
+
+    fn parse(&self, action: ActionJson) -> Option<Result<ChatEvent, serde_json::Error>> {
+        if self.is_empty() {
+            return None;
+        }
+        match action {
+            ActionJson::Raw(raw_action) => {
+                let action: RegisteredAction = serde_json::from_str(raw_action.get()).ok()?;
+                action
+                    .add_chat_item_action?
+                    .item
+                    .iter()
+                    .find_map(|(name, renderer)| {
+                        self.parse_renderer(name, ActionJson::Raw(renderer))
+                    })
             }
+            ActionJson::Value(action) => action
+                .get("addChatItemAction")?
+                .get("item")?
+                .as_object()?
+                .iter()
+                .find_map(|(name, renderer)| {
+                    self.parse_renderer(name, ActionJson::Value(renderer))
+                }),
         }
     }
 
-    fn thumbnails(&self) -> Vec<Thumbnail> {
-        match self {
-            Renderer::LiveChatTextMessageRenderer(renderer) => renderer
-                .message_renderer_base
-                .author_photo
-                .thumbnails
-                .clone(),
-            Renderer::LiveChatPaidMessageRenderer(renderer) => renderer
-                .live_chat_text_message_renderer
-                .message_renderer_base
-                .author_photo
-                .thumbnails
-                .clone(),
-            Renderer::LiveChatMembershipItemRenderer(renderer) => renderer
-                .message_renderer_base
-                .author_photo
-                .thumbnails
-                .clone(),
-            Renderer::LiveChatPaidStickerRenderer(renderer) => renderer
-                .message_renderer_base
-                .author_photo
-                .thumbnails
-                .clone(),
-        }
+    fn parse_renderer(
+        &self,
+        name: &str,
+        renderer: ActionJson,
+    ) -> Option<Result<ChatEvent, serde_json::Error>> {
+        Some(self.renderers.get(name)?(renderer))
     }
+}
 
-    fn channel_id(&self) -> String {
-        match self {
-            Renderer::LiveChatTextMessageRenderer(renderer) => renderer
-                .message_renderer_base
-                .author_external_channel_id
-                .clone(),
-            Renderer::LiveChatPaidMessageRenderer(renderer) => renderer
-                .live_chat_text_message_renderer
-                .message_renderer_base
-                .author_external_channel_id
-                .clone(),
-            Renderer::LiveChatMembershipItemRenderer(renderer) => renderer
-                .message_renderer_base
-                .author_external_channel_id
-                .clone(),
-            Renderer::LiveChatPaidStickerRenderer(renderer) => renderer
-                .message_renderer_base
-                .author_external_channel_id
-                .clone(),
+/// Names the action and, for `addChatItemAction`, its renderer.
+fn describe_action(raw_action: &serde_json::Value) -> String {
+    let action = raw_action.as_object().and_then(|action| {
+        action
+            .iter()
+            .find(|(name, _)| name.as_str() != "clickTrackingParams")
+    });
+    let Some((name, body)) = action else {
+        return "action".to_string();
+    };
+    let renderer = body
+        .get("item")
+        .and_then(serde_json::Value::as_object)
+        .and_then(|item| item.keys().next());
+    match renderer {
+        Some(renderer) => format!("`{}` in `{}`", renderer, name),
+        None => format!("`{}`", name),
+    }
+}
+
+fn parse_action_to_event(action: Action) -> Option<ChatEvent> {
+    if let Some(deleted) = action.mark_chat_item_as_deleted_action {
+        Some(ChatEvent::Deletion(Deletion::Item {
+            id: deleted.target_item_id,
+        }))
+    } else if let Some(deleted) = action.mark_chat_items_by_author_as_deleted_action {
+        Some(ChatEvent::Deletion(Deletion::Author {
+            channel_id: deleted.external_channel_id,
+        }))
+    } else if let Some(ticker) = action.add_live_chat_ticker_item_action {
+        parse_ticker(ticker.item).map(ChatEvent::Ticker)
+    } else if let Some(command) = action.add_banner_to_live_chat_command {
+        let mut renderer = command.banner_renderer.live_chat_banner_renderer;
+        let redirect = renderer
+            .contents
+            .as_mut()
+            .and_then(|contents| contents.live_chat_banner_redirect_renderer.take());
+        if let Some(redirect) = redirect {
+            return Some(ChatEvent::Raid(parse_raid(renderer.action_id, redirect)));
         }
+        Some(ChatEvent::Banner(BannerEvent::Added(Box::new(
+            parse_banner(renderer),
+        ))))
+    } else if let Some(command) = action.remove_banner_for_live_chat_command {
+        Some(ChatEvent::Banner(BannerEvent::Removed {
+            action_id: command.target_action_id,
+        }))
+    } else if let Some(show) = action.show_live_chat_action_panel_action {
+        let panel = show.panel_to_show.live_chat_action_panel_renderer;
+        Some(ChatEvent::Poll(PollEvent::Shown {
+            panel_id: panel.id,
+            poll: parse_poll(panel.contents?.poll_renderer?),
+        }))
+    } else if let Some(update) = action.update_live_chat_poll_action {
+        Some(ChatEvent::Poll(PollEvent::Updated(parse_poll(
+            update.poll_to_update.poll_renderer?,
+        ))))
+    } else if let Some(close) = action.close_live_chat_action_panel_action {
+        Some(ChatEvent::Poll(PollEvent::Closed {
+            panel_id: close.target_panel_id,
+        }))
+    } else {
+        parse_item_to_event(action.add_chat_item_action?.item)
     }
+}
 
-    fn time_stamp(&self) -> Option<DateTime<Utc>> {
-        let timestamp_usec = match self {
-            Renderer::LiveChatTextMessageRenderer(renderer) => {
-                renderer.message_renderer_base.timestamp_usec.clone()
-            }
-            Renderer::LiveChatPaidMessageRenderer(renderer) => renderer
-                .live_chat_text_message_renderer
-                .message_renderer_base
+fn parse_item_to_event(item: ActionItem) -> Option<ChatEvent> {
+    if let Some(renderer) = item.live_chat_sponsorships_gift_purchase_announcement_renderer {
+        return Some(ChatEvent::Gift(GiftMembership::Purchase(
+            parse_gift_purchase(renderer),
+        )));
+    }
+    if let Some(renderer) = item.live_chat_sponsorships_gift_redemption_announcement_renderer {
+        return Some(ChatEvent::Gift(GiftMembership::Redemption(
+            parse_gift_redemption(renderer),
+        )));
+    }
+    if let Some(renderer) = item.live_chat_viewer_engagement_message_renderer {
+        return Some(ChatEvent::Engagement(Engagement {
+            id: renderer.id,
+            icon: renderer.icon.map(|icon| icon.icon_type),
+            message: parse_message(
+                renderer
+                    .message
+                    .map(|message| message.runs)
+                    .unwrap_or_default(),
+            ),
+            timestamp: renderer
                 .timestamp_usec
-                .clone(),
-            Renderer::LiveChatMembershipItemRenderer(renderer) => {
-                renderer.message_renderer_base.timestamp_usec.clone()
-            }
-            Renderer::LiveChatPaidStickerRenderer(renderer) => {
-                renderer.message_renderer_base.timestamp_usec.clone()
+                .as_deref()
+                .and_then(parse_timestamp_usec),
+        }));
+    }
+    let message_renderer = renderer_from_item(item)?;
+    let to_event = match message_renderer {
+        Renderer::LiveChatTextMessageRenderer(_) => ChatEvent::Chat,
+        Renderer::LiveChatPaidMessageRenderer(_) => ChatEvent::SuperChat,
+        Renderer::LiveChatMembershipItemRenderer(_) => ChatEvent::Membership,
+        Renderer::LiveChatPaidStickerRenderer(_) => ChatEvent::Sticker,
+    };
+    Some(to_event(message_renderer.into_chat_item()))
+}
+
+fn parse_ticker(item: TickerItem) -> Option<Ticker> {
+    let (kind, base, photo, amount, detail, sticker) =
+        if let Some(renderer) = item.live_chat_ticker_paid_message_item_renderer {
+            (
+                TickerKind::PaidMessage,
+                renderer.ticker_renderer_base,
+                renderer.author_photo,
+                renderer.amount.map(|amount| amount.simple_text),
+                None,
+                None,
+            )
+        } else if let Some(renderer) = item.live_chat_ticker_paid_sticker_item_renderer {
+            let sticker = renderer
+                .ticker_thumbnails
+                .and_then(|stickers| stickers.into_iter().next())
+                .and_then(|sticker| {
+                    parse_thumbnails_to_image_item(
+                        sticker.thumbnails,
+                        Some(sticker.accessibility.accessibility_data.label),
+                    )
+                });
+            (
+                TickerKind::PaidSticker,
+                renderer.ticker_renderer_base,
+                renderer.author_photo,
+                None,
+                None,
+                sticker,
+            )
+        } else {
+            let renderer = item.live_chat_ticker_sponsor_item_renderer?;
+            let detail = renderer
+                .detail_text
+                .map(|detail_text| text_of(&detail_text));
+            (
+                TickerKind::Sponsor,
+                renderer.ticker_renderer_base,
+                renderer.sponsor_photo,
+                None,
+                detail,
+                None,
+            )
+        };
+    // The ticker only carries the author's photo; the linked item has the rest.
+    let linked = base
+        .show_item_endpoint
+        .and_then(|endpoint| parse_item_to_event(endpoint.show_live_chat_item_endpoint.renderer));
+    let chat_item_id = linked
+        .as_ref()
+        .and_then(|chat_event| chat_event.id())
+        .map(str::to_string);
+    let linked_chat_item = linked.and_then(ChatEvent::into_chat_item);
+    let author = match &linked_chat_item {
+        Some(chat_item) => chat_item.author.clone(),
+        None => Author {
+            name: None,
+            thumbnail: parse_thumbnails_to_image_item(photo.thumbnails, None),
+            channel_id: base.author_external_channel_id,
+            badge: None,
+        },
+    };
+    let amount = amount.or_else(|| Some(linked_chat_item?.superchat?.amount));
+    Some(Ticker {
+        id: base.id,
+        kind,
+        author,
+        amount,
+        detail,
+        sticker,
+        duration: Duration::from_secs(base.duration_sec),
+        full_duration: Duration::from_secs(base.full_duration_sec),
+        chat_item_id,
+    })
+}
+
+fn parse_banner(renderer: LiveChatBannerRenderer) -> Banner {
+    let header = renderer
+        .header
+        .and_then(|header| header.live_chat_banner_header_renderer.text)
+        .map(|text| text_of(&text));
+    Banner {
+        action_id: renderer.action_id,
+        header,
+        chat_item: renderer
+            .contents
+            .and_then(parse_item_to_event)
+            .and_then(ChatEvent::into_chat_item),
+    }
+}
+
+fn parse_raid(action_id: String, renderer: LiveChatBannerRedirectRenderer) -> Raid {
+    // e.g. ["@raider", " and their viewers just joined. Say hello!"]
+    let from_channel = renderer
+        .banner_message
+        .runs
+        .iter()
+        .find_map(|run| match run {
+            MessageRun::MessageText { text, .. } if !text.trim().is_empty() => {
+                Some(text.trim().to_string())
             }
+            _ => None,
+        });
+    let to_video_id = renderer
+        .inline_action_button
+        .and_then(|button| button.button_renderer.command?.watch_endpoint)
+        .map(|watch_endpoint| watch_endpoint.video_id);
+    Raid {
+        action_id,
+        from_channel,
+        to_video_id,
+        text: runs_text(&renderer.banner_message.runs),
+    }
+}
+
+fn parse_poll(renderer: PollRenderer) -> Poll {
+    let header = renderer.header.map(|header| header.poll_header_renderer);
+    let (question, metadata) = match header {
+        Some(header) => (header.poll_question, header.metadata_text),
+        None => (None, None),
+    };
+    Poll {
+        id: renderer.live_chat_poll_id,
+        question: question.map(|question| text_of(&question)),
+        choices: renderer
+            .choices
+            .into_iter()
+            .map(|choice| PollChoice {
+                text: choice.text.map(|text| text_of(&text)).unwrap_or_default(),
+                vote_ratio: choice.vote_ratio,
+                vote_percentage: choice
+                    .vote_percentage
+                    .map(|percentage| text_of(&percentage)),
+            })
+            .collect(),
+        metadata: metadata.map(|metadata| text_of(&metadata)),
+    }
+}
+
+fn parse_gift_purchase(
+    renderer: LiveChatSponsorshipsGiftPurchaseAnnouncementRenderer,
+) -> GiftPurchase {
+    let header = renderer.header.live_chat_sponsorships_header_renderer;
+    let runs = header.primary_text.runs;
+    // e.g. ["Sent ", "5", " ", "<channel name>", " gift memberships"]
+    let count_index = runs.iter().position(
+        |run| matches!(run, MessageRun::MessageText { text, .. } if text.trim().parse::<u32>().is_ok()),
+    );
+    let count = count_index
+        .and_then(|index| match &runs[index] {
+            MessageRun::MessageText { text, .. } => text.trim().parse().ok(),
+            MessageRun::MessageEmoji { .. } => None,
+        })
+        .unwrap_or(1);
+    let channel_name = count_index.and_then(|index| {
+        let rest = &runs[index + 1..];
+        rest[..rest.len().saturating_sub(1)]
+            .iter()
+            .find_map(|run| match run {
+                MessageRun::MessageText { text, .. } if !text.trim().is_empty() => {
+                    Some(text.trim().to_string())
+                }
+                _ => None,
+            })
+    });
+    let author_name = header.author_name.map(|name| name.simple_text);
+    GiftPurchase {
+        id: renderer.id,
+        gifter: Author {
+            thumbnail: parse_thumbnails_to_image_item(
+                header.author_photo.thumbnails,
+                author_name.clone(),
+            ),
+            name: author_name,
+            channel_id: renderer.author_external_channel_id,
+            badge: header.author_badges.and_then(parse_member_badge),
+        },
+        count,
+        channel_name,
+        timestamp: parse_timestamp_usec(&renderer.timestamp_usec),
+    }
+}
+
+fn parse_gift_redemption(
+    renderer: LiveChatSponsorshipsGiftRedemptionAnnouncementRenderer,
+) -> GiftRedemption {
+    // e.g. ["received a gift membership by ", "<gifter name>"]
+    let gifter_name = match renderer.message.runs.as_slice() {
+        [_, .., MessageRun::MessageText { text, .. }] => Some(text.trim().to_string()),
+        _ => None,
+    };
+    let author_name = renderer.author_name.map(|name| name.simple_text);
+    GiftRedemption {
+        id: renderer.id,
+        recipient: Author {
+            thumbnail: parse_thumbnails_to_image_item(
+                renderer.author_photo.thumbnails,
+                author_name.clone(),
+            ),
+            name: author_name,
+            channel_id: renderer.author_external_channel_id,
+            badge: renderer.author_badges.and_then(parse_member_badge),
+        },
+        gifter_name,
+        timestamp: parse_timestamp_usec(&renderer.timestamp_usec),
+    }
+}
+
+fn parse_renderer_to_chat_item<R: ChatRenderer + ?Sized>(message_renderer: &R) -> ChatItem {
+    ChatItemParts {
+        id: message_renderer.id(),
+        author_name: message_renderer.author_name(),
+        thumbnails: message_renderer.thumbnails(),
+        channel_id: message_renderer.channel_id(),
+        runs: message_renderer.runs(),
+        timestamp: message_renderer.time_stamp(),
+        author_badges: message_renderer.author_badge(),
+        superchat: message_renderer.superchat(),
+        membership: message_renderer.membership(),
+    }
+    .into_chat_item()
+}
+
+/// Owned fields of a renderer, from which every [`ChatItem`] is built.
+struct ChatItemParts {
+    id: String,
+    author_name: Option<String>,
+    thumbnails: Vec<Thumbnail>,
+    channel_id: String,
+    runs: Vec<MessageRun>,
+    timestamp: Option<DateTime<Utc>>,
+    author_badges: Option<Vec<AuthorBadge>>,
+    superchat: Option<SuperChat>,
+    membership: Option<Membership>,
+}
+
+impl ChatItemParts {
+    fn into_chat_item(self) -> ChatItem {
+        let mut chat_item = ChatItem {
+            id: self.id,
+            author: Author {
+                thumbnail: parse_thumbnails_to_image_item(
+                    self.thumbnails,
+                    self.author_name.clone(),
+                ),
+                name: self.author_name,
+                channel_id: self.channel_id,
+                badge: None,
+            },
+            message: parse_message(self.runs),
+            superchat: self.superchat,
+            membership: self.membership,
+            is_membership: false,
+            is_verified: false,
+            is_owner: false,
+            is_moderator: false,
+            timestamp: self.timestamp,
         };
-        Some(Utc.timestamp_nanos(timestamp_usec.parse::<i64>().ok()? * 1000))
+        apply_badges(self.author_badges, &mut chat_item);
+        chat_item
     }
+}
+
+/// Accessors the parser needs to turn a renderer into a [`ChatItem`]. Implement it
+/// for a `Deserialize` type and register it with [`RendererRegistry::register`]
+/// to parse a renderer this crate does not support yet.
+pub trait ChatRenderer {
+    fn id(&self) -> String;
+    fn author_name(&self) -> Option<String>;
+    fn channel_id(&self) -> String;
+    fn thumbnails(&self) -> Vec<Thumbnail>;
+    fn runs(&self) -> Vec<MessageRun>;
+    fn time_stamp(&self) -> Option<DateTime<Utc>>;
 
     fn author_badge(&self) -> Option<Vec<AuthorBadge>> {
-        match self {
-            Renderer::LiveChatTextMessageRenderer(renderer) => {
-                renderer.message_renderer_base.author_badges.clone()
-            }
-            Renderer::LiveChatPaidMessageRenderer(renderer) => renderer
-                .live_chat_text_message_renderer
-                .message_renderer_base
-                .author_badges
-                .clone(),
-            Renderer::LiveChatMembershipItemRenderer(renderer) => {
-                renderer.message_renderer_base.author_badges.clone()
-            }
-            Renderer::LiveChatPaidStickerRenderer(renderer) => {
-                renderer.message_renderer_base.author_badges.clone()
-            }
-        }
+        None
     }
 
-    fn process_badge(&self, chat_item: &mut ChatItem) {
-        if let Some(author_badges) = self.author_badge() {
-            for author_badge in author_badges {
-                let badge_renderer = author_badge.live_chat_author_badge_renderer;
-                let icon_type = badge_renderer.icon.map(|icon| icon.icon_type);
-                let tooltip = badge_renderer.tooltip.clone();
-                if let Some(custom_thumbnail) = badge_renderer.custom_thumbnail {
-                    let badge = (|| {
-                        Some(Badge {
-                            thumbnail: parse_thumbnails_to_image_item(
-                                custom_thumbnail.thumbnails,
-                                Some(tooltip.clone()),
-                            )?,
-                            label: tooltip.clone(),
-                        })
-                    })();
-                    if let Some(badge) = badge {
-                        chat_item.author.badge = Some(badge); // mutate
-                    }
-                    chat_item.is_membership = true; // mutate
-                } else if let Some("OWNER" | "VERIFIED" | "MODERATOR") = icon_type.as_deref() {
-                    chat_item.is_owner = true;
+    fn superchat(&self) -> Option<SuperChat> {
+        None
+    }
+
+    fn membership(&self) -> Option<Membership> {
+        None
+    }
+}
+
+fn apply_badges(author_badges: Option<Vec<AuthorBadge>>, chat_item: &mut ChatItem) {
+    if let Some(author_badges) = author_badges {
+        for author_badge in author_badges {
+            let badge_renderer = author_badge.live_chat_author_badge_renderer;
+            let icon_type = badge_renderer.icon.map(|icon| icon.icon_type);
+            let tooltip = badge_renderer.tooltip.clone();
+            if let Some(custom_thumbnail) = badge_renderer.custom_thumbnail {
+                if let Some(badge) = parse_custom_badge(custom_thumbnail, tooltip) {
+                    chat_item.author.badge = Some(badge); // mutate
+                }
+                chat_item.is_membership = true; // mutate
+            } else {
+                match icon_type.as_deref() {
+                    Some("OWNER") => chat_item.is_owner = true,
+                    Some("VERIFIED") => chat_item.is_verified = true,
+                    Some("MODERATOR") => chat_item.is_moderator = true,
+                    _ => {}
                 }
             }
         }
     }
+}
 
-    fn superchat(&self) -> Option<SuperChat> {
-        match self {
-            Renderer::LiveChatTextMessageRenderer(_) => None,
-            Renderer::LiveChatPaidMessageRenderer(renderer) => Some(SuperChat {
-                amount: renderer.purchase_amount_text.simple_text.clone(),
-                color: convert_color_to_hex6(renderer.body_background_color),
-                sticker: None,
-            }),
-            Renderer::LiveChatMembershipItemRenderer(_) => None,
-            Renderer::LiveChatPaidStickerRenderer(renderer) => Some(SuperChat {
-                amount: renderer.purchase_amount_text.simple_text.clone(),
-                color: convert_color_to_hex6(renderer.background_color),
-                sticker: parse_thumbnails_to_image_item(
-                    renderer.sticker.thumbnails.clone(),
-                    Some(
-                        renderer
-                            .sticker
-                            .accessibility
-                            .accessibility_data
-                            .label
-                            .clone(),
+pub enum Renderer {
+    LiveChatTextMessageRenderer(LiveChatTextMessageRenderer),
+    LiveChatPaidMessageRenderer(LiveChatPaidMessageRenderer),
+    LiveChatMembershipItemRenderer(LiveChatMembershipItemRenderer),
+    LiveChatPaidStickerRenderer(LiveChatPaidStickerRenderer),
+}
+impl Renderer {
+    /// Builds the [`ChatItem`] by moving the renderer's fields.
+    pub fn into_chat_item(self) -> ChatItem {
+        let (base, runs, superchat, membership) = match self {
+            Renderer::LiveChatTextMessageRenderer(renderer) => (
+                renderer.message_renderer_base,
+                renderer.message.runs,
+                None,
+                None,
+            ),
+            Renderer::LiveChatPaidMessageRenderer(renderer) => {
+                let superchat = SuperChat {
+                    amount: renderer.purchase_amount_text.simple_text,
+                    color: convert_color_to_hex6(renderer.body_background_color),
+                    sticker: None,
+                };
+                let renderer = renderer.live_chat_text_message_renderer;
+                (
+                    renderer.message_renderer_base,
+                    renderer.message.runs,
+                    Some(superchat),
+                    None,
+                )
+            }
+            Renderer::LiveChatMembershipItemRenderer(renderer) => {
+                let runs = renderer
+                    .header_sub_text
+                    .map(into_header_sub_text_runs)
+                    .unwrap_or_default();
+                let membership = parse_membership(
+                    renderer.header_primary_text.as_ref(),
+                    &runs,
+                    renderer.message,
+                );
+                (renderer.message_renderer_base, runs, None, Some(membership))
+            }
+            Renderer::LiveChatPaidStickerRenderer(renderer) => {
+                let label = renderer.sticker.accessibility.accessibility_data.label;
+                let superchat = SuperChat {
+                    amount: renderer.purchase_amount_text.simple_text,
+                    color: convert_color_to_hex6(renderer.background_color),
+                    sticker: parse_thumbnails_to_image_item(
+                        renderer.sticker.thumbnails,
+                        Some(label),
                     ),
-                ),
-            }),
+                };
+                (
+                    renderer.message_renderer_base,
+                    Vec::new(),
+                    Some(superchat),
+                    None,
+                )
+            }
+        };
+        ChatItemParts {
+            id: base.id,
+            author_name: base.author_name.map(|name| name.simple_text),
+            thumbnails: base.author_photo.thumbnails,
+            channel_id: base.author_external_channel_id,
+            runs,
+            timestamp: parse_timestamp_usec(&base.timestamp_usec),
+            author_badges: base.author_badges,
+            superchat,
+            membership,
         }
+        .into_chat_item()
     }
 }
+fn parse_membership(
+    primary_text: Option<&Message>,
+    sub_runs: &[MessageRun],
+    message: Option<Message>,
+) -> Membership {
+    let message = message
+        .map(|message| parse_message(message.runs))
+        .unwrap_or_default();
+    if let Some(primary_text) = primary_text {
+        // e.g. headerPrimaryText ["Member for ", "12", " months"], headerSubText "<tier>"
+        let months = runs_text(&primary_text.runs)
+            .split(|c: char| !c.is_ascii_digit())
+            .find_map(|digits| digits.parse().ok());
+        let tier = Some(runs_text(sub_runs)).filter(|tier| !tier.is_empty());
+        return Membership {
+            kind: MembershipKind::Milestone,
+            tier,
+            months,
+            message,
+        };
+    }
+    // e.g. ["Welcome to ", "<tier>", "!"] or ["Upgraded membership to ", "<tier>", "!"].
+    // Nothing but the English text marks an upgrade, hence `hl=en` by default.
+    let kind = if runs_text(sub_runs).to_lowercase().starts_with("upgraded") {
+        MembershipKind::Upgrade
+    } else {
+        MembershipKind::New
+    };
+    let tier = match sub_runs {
+        [_, tier @ .., _] if !tier.is_empty() => Some(runs_text(tier).trim().to_string()),
+        _ => None,
+    };
+    Membership {
+        kind,
+        tier,
+        months: None,
+        message,
+    }
+}
+
 impl From<LiveChatTextMessageRenderer> for Renderer {
     fn from(value: LiveChatTextMessageRenderer) -> Self {
         Self::LiveChatTextMessageRenderer(value)
@@ -370,8 +990,7 @@ impl From<LiveChatPaidStickerRenderer> for Renderer {
         Self::LiveChatPaidStickerRenderer(value)
     }
 }
-fn renderer_from_action(action: Action) -> Option<Renderer> {
-    let item = action.add_chat_item_action?.item;
+fn renderer_from_item(item: ActionItem) -> Option<Renderer> {
     #[allow(clippy::manual_map)]
     if let Some(renderer) = item.live_chat_text_message_renderer {
         Some(renderer.into())
@@ -386,14 +1005,84 @@ fn renderer_from_action(action: Action) -> Option<Renderer> {
     }
 }
 
+fn parse_custom_badge(custom_thumbnail: CustomThumbnail, tooltip: String) -> Option<Badge> {
+    Some(Badge {
+        thumbnail: parse_thumbnails_to_image_item(
+            custom_thumbnail.thumbnails,
+            Some(tooltip.clone()),
+        )?,
+        label: tooltip,
+    })
+}
+
+/// Returns the membership badge among `author_badges`, if any.
+fn parse_member_badge(author_badges: Vec<AuthorBadge>) -> Option<Badge> {
+    author_badges.into_iter().find_map(|author_badge| {
+        let badge_renderer = author_badge.live_chat_author_badge_renderer;
+        parse_custom_badge(badge_renderer.custom_thumbnail?, badge_renderer.tooltip)
+    })
+}
+
+fn header_sub_text_runs(header_sub_text: &HeaderSubText) -> Vec<MessageRun> {
+    match (&header_sub_text.runs, &header_sub_text.simple_text) {
+        (Some(runs), _) => runs.clone(),
+        (None, Some(text)) => vec![MessageRun::MessageText {
+            text: text.clone(),
+            bold: None,
+            italics: None,
+            navigation_endpoint: None,
+        }],
+        (None, None) => Vec::new(),
+    }
+}
+
+fn into_header_sub_text_runs(header_sub_text: HeaderSubText) -> Vec<MessageRun> {
+    match (header_sub_text.runs, header_sub_text.simple_text) {
+        (Some(runs), _) => runs,
+        (None, Some(text)) => vec![MessageRun::MessageText {
+            text,
+            bold: None,
+            italics: None,
+            navigation_endpoint: None,
+        }],
+        (None, None) => Vec::new(),
+    }
+}
+
+fn text_of(text: &HeaderSubText) -> String {
+    runs_text(&header_sub_text_runs(text))
+}
+
+/// Concatenates the text of `runs`, skipping emojis.
+fn runs_text(runs: &[MessageRun]) -> String {
+    runs.iter()
+        .filter_map(|run| match run {
+            MessageRun::MessageText { text, .. } => Some(text.as_str()),
+            MessageRun::MessageEmoji { .. } => None,
+        })
+        .collect()
+}
+
+fn parse_timestamp_usec(timestamp_usec: &str) -> Option<DateTime<Utc>> {
+    Some(Utc.timestamp_nanos(timestamp_usec.parse::<i64>().ok()? * 1000))
+}
+
 fn parse_thumbnails_to_image_item(
     thumbnails: Vec<Thumbnail>,
     alt: Option<String>,
 ) -> Option<ImageItem> {
-    let thumbnail = thumbnails.into_iter().next()?;
+    let url = thumbnails.first()?.url.clone();
     Some(ImageItem {
-        url: thumbnail.url,
+        url,
         alt,
+        sizes: thumbnails
+            .into_iter()
+            .map(|thumbnail| ImageSize {
+                url: thumbnail.url,
+                width: thumbnail.width,
+                height: thumbnail.height,
+            })
+            .collect(),
     })
 }
 
@@ -411,36 +1100,719 @@ fn convert_color_to_hex6(color_number: isize) -> String {
     hex_string
 }
 
+fn parse_text_run(
+    text: String,
+    style: TextStyle,
+    navigation_endpoint: Option<NavigationEndpoint>,
+) -> MessageItem {
+    if let Some(endpoint) = navigation_endpoint {
+        if let Some(browse_endpoint) = endpoint.browse_endpoint {
+            return MessageItem::Mention {
+                text,
+                channel_id: Some(browse_endpoint.browse_id),
+                style,
+            };
+        }
+        let url = match (endpoint.url_endpoint, endpoint.watch_endpoint) {
+            (Some(url_endpoint), _) => Some(resolve_redirect_url(url_endpoint.url)),
+            (None, Some(watch_endpoint)) => Some(format!(
+                "https://www.youtube.com/watch?v={}",
+                watch_endpoint.video_id
+            )),
+            (None, None) => None,
+        };
+        if let Some(url) = url {
+            return MessageItem::Link { text, url, style };
+        }
+    }
+    let trimmed = text.trim();
+    if trimmed.len() > 1 && trimmed.starts_with('@') && !trimmed.contains(char::is_whitespace) {
+        MessageItem::Mention {
+            text,
+            channel_id: None,
+            style,
+        }
+    } else if style == TextStyle::default() {
+        MessageItem::Text(text)
+    } else {
+        MessageItem::StyledText { text, style }
+    }
+}
+
+/// Returns the target of a `https://www.youtube.com/redirect?q=<target>` link,
+/// or `url` itself for other links.
+fn resolve_redirect_url(url: String) -> String {
+    let target = Url::parse(&url).ok().and_then(|parsed| {
+        if parsed.path() != "/redirect" {
+            return None;
+        }
+        parsed
+            .query_pairs()
+            .find(|(key, _)| key == "q")
+            .map(|(_, target)| target.into_owned())
+    });
+    match target {
+        Some(target) => target,
+        None if url.starts_with('/') => format!("https://www.youtube.com{}", url),
+        None => url,
+    }
+}
+
 fn parse_message(runs: Vec<MessageRun>) -> Vec<MessageItem> {
     runs.into_iter()
         .map(|run| match run {
-            MessageRun::MessageText { text } => MessageItem::Text(text),
+            MessageRun::MessageText {
+                text,
+                bold,
+                italics,
+                navigation_endpoint,
+            } => parse_text_run(
+                text,
+                TextStyle {
+                    bold: bold == Some(true),
+                    italics: italics == Some(true),
+                },
+                navigation_endpoint,
+            ),
             MessageRun::MessageEmoji {
                 emoji,
                 variant_ids: _,
                 is_custome_emoji,
             } => {
-                let thumbnail = emoji.image.thumbnails.into_iter().next();
-                let shortcut = emoji
-                    .shortcuts
-                    .and_then(|shortcuts| shortcuts.into_iter().next());
-                let image_item = thumbnail.map(|thumbnail| ImageItem {
-                    url: thumbnail.url,
-                    alt: shortcut.clone(),
-                });
+                // YouTube sends the flag on the emoji itself
+                let is_custome_emoji = is_custome_emoji.or(emoji.is_custom_emoji);
+                let shortcuts = emoji.shortcuts.unwrap_or_default();
+                let shortcut = shortcuts.first().cloned();
+                let image_item =
+                    parse_thumbnails_to_image_item(emoji.image.thumbnails, shortcut.clone());
 
                 let emoji_text = if is_custome_emoji == Some(true) {
                     shortcut
                 } else {
-                    Some(emoji.emoji_id)
+                    Some(emoji.emoji_id.clone())
                 };
 
                 MessageItem::Emoji(EmojiItem {
                     image_item,
                     emoji_text,
                     is_custome_emoji,
+                    emoji_id: Some(emoji.emoji_id),
+                    shortcuts,
+                    search_terms: emoji.search_terms.unwrap_or_default(),
+                    supports_skin_tone: emoji.supports_skin_tone,
                 })
             }
         })
         .collect()
 }
+
+#[cfg(test)]
+mod parser_tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn response(actions: Value) -> GetLiveChatResponse {
+        serde_json::from_value(json!({
+            "responseContext": {},
+            "continuationContents": {
+                "liveChatContinuation": {
+                    "continuations": [{
+                        "timedContinuationData": { "timeoutMs": 5000, "continuation": "next" }
+                    }],
+                    "actions": actions
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    fn message_renderer_base(id: &str) -> Value {
+        json!({
+            "authorName": { "simpleText": "author" },
+            "authorPhoto": {
+                "thumbnails": [{ "url": "https://yt4.ggpht.com/photo=s32", "width": 32, "height": 32 }]
+            },
+            "contextMenuEndpoint": {
+                "commandMetadata": { "webCommandMetadata": { "ignoreNavigation": true } },
+                "liveChatItemContextMenuEndpoint": { "params": "params" }
+            },
+            "id": id,
+            "timestampUsec": "1676000000000000",
+            "authorExternalChannelId": "UCxxxxxxxxxxxxxxxxxxxxxx",
+            "contextMenuAccessibility": { "accessibilityData": { "label": "Chat actions" } }
+        })
+    }
+
+    fn with_base(id: &str, fields: Value) -> Value {
+        let mut renderer = message_renderer_base(id);
+        renderer
+            .as_object_mut()
+            .unwrap()
+            .extend(fields.as_object().unwrap().clone());
+        renderer
+    }
+
+    #[test]
+    fn test_parse_chat_events() {
+        let (chat_events, continuation) = parse_chat_events(response(json!([
+            { "addChatItemAction": { "item": {
+                "liveChatTextMessageRenderer": with_base("a", json!({
+                    "message": { "runs": [{ "text": "hello" }] }
+                }))
+            } } },
+            { "markChatItemAsDeletedAction": {
+                "deletedStateMessage": { "runs": [{ "text": "[message retracted]" }] },
+                "targetItemId": "a"
+            } },
+            { "markChatItemsByAuthorAsDeletedAction": {
+                "externalChannelId": "UCxxxxxxxxxxxxxxxxxxxxxx"
+            } }
+        ])));
+        assert_eq!(continuation.unwrap().token(), "next");
+        assert!(matches!(&chat_events[..], [
+                ChatEvent::Chat(chat_item),
+                ChatEvent::Deletion(Deletion::Item { id }),
+                ChatEvent::Deletion(Deletion::Author { channel_id }),
+            ] if chat_item.id == "a" && id == "a" && channel_id == "UCxxxxxxxxxxxxxxxxxxxxxx"));
+    }
+
+    #[test]
+    fn test_parse_continuation() {
+        let continuations = |continuations: Value| {
+            let continuations: Vec<ContinuationData> =
+                serde_json::from_value(continuations).unwrap();
+            parse_continuation(continuations)
+        };
+        assert_eq!(
+            continuations(json!([{ "invalidationContinuationData": {
+                "invalidationId": {
+                    "objectSource": 1056,
+                    "objectId": "object",
+                    "topic": "chat~jfKfPfyJRdk~5893609",
+                    "subscribeToGcmTopics": true,
+                    "protoCreationTimestampMs": "1676000146000"
+                },
+                "timeoutMs": 10000,
+                "continuation": "live"
+            } }])),
+            Some(Continuation::Invalidation {
+                continuation: "live".to_string(),
+                timeout: Duration::from_secs(10),
+                topic: "chat~jfKfPfyJRdk~5893609".to_string(),
+            })
+        );
+        assert_eq!(
+            continuations(json!([
+                { "playerSeekContinuationData": { "continuation": "seek" } },
+                { "liveChatReplayContinuationData": {
+                    "timeUntilLastMessageMsec": 1500,
+                    "continuation": "replay"
+                } }
+            ])),
+            Some(Continuation::PlayerSeek {
+                continuation: "seek".to_string()
+            })
+        );
+        assert_eq!(
+            continuations(json!([{ "reloadContinuationData": { "continuation": "reload" } }]))
+                .map(Continuation::into_token),
+            Some("reload".to_string())
+        );
+        assert_eq!(continuations(json!([{}])), None);
+    }
+
+    #[test]
+    fn test_parse_gift_memberships() {
+        let photo = json!({
+            "thumbnails": [{ "url": "https://yt4.ggpht.com/photo=s32", "width": 32, "height": 32 }]
+        });
+        let (chat_events, _) = parse_chat_events(response(json!([
+            { "addChatItemAction": { "item": {
+                "liveChatSponsorshipsGiftPurchaseAnnouncementRenderer": {
+                    "id": "purchase",
+                    "timestampUsec": "1676000000000000",
+                    "authorExternalChannelId": "UCgifter",
+                    "header": { "liveChatSponsorshipsHeaderRenderer": {
+                        "authorName": { "simpleText": "gifter" },
+                        "authorPhoto": photo,
+                        "primaryText": { "runs": [
+                            { "text": "Sent ", "bold": true },
+                            { "text": "5", "bold": true },
+                            { "text": " ", "bold": true },
+                            { "text": "Channel", "bold": true },
+                            { "text": " gift memberships", "bold": true }
+                        ] }
+                    } }
+                }
+            } } },
+            { "addChatItemAction": { "item": {
+                "liveChatSponsorshipsGiftRedemptionAnnouncementRenderer": {
+                    "id": "redemption",
+                    "timestampUsec": "1676000000000001",
+                    "authorExternalChannelId": "UCrecipient",
+                    "authorName": { "simpleText": "recipient" },
+                    "authorPhoto": photo,
+                    "message": { "runs": [
+                        { "text": "received a gift membership by " },
+                        { "text": "gifter", "bold": true }
+                    ] }
+                }
+            } } }
+        ])));
+        assert!(matches!(&chat_events[..], [
+                ChatEvent::Gift(GiftMembership::Purchase(purchase)),
+                ChatEvent::Gift(GiftMembership::Redemption(redemption)),
+            ] if purchase.count == 5
+                && purchase.channel_name.as_deref() == Some("Channel")
+                && purchase.gifter.channel_id == "UCgifter"
+                && redemption.recipient.name.as_deref() == Some("recipient")
+                && redemption.gifter_name.as_deref() == Some("gifter")));
+        assert_eq!(chat_events[1].id(), Some("redemption"));
+    }
+
+    #[test]
+    fn test_parse_membership() {
+        let (chat_events, _) = parse_chat_events(response(json!([
+            { "addChatItemAction": { "item": {
+                "liveChatMembershipItemRenderer": with_base("new", json!({
+                    "headerSubText": { "runs": [
+                        { "text": "Welcome to " }, { "text": "Gold" }, { "text": "!" }
+                    ] },
+                    "authorBadges": []
+                }))
+            } } },
+            { "addChatItemAction": { "item": {
+                "liveChatMembershipItemRenderer": with_base("milestone", json!({
+                    "headerPrimaryText": { "runs": [
+                        { "text": "Member for " }, { "text": "12" }, { "text": " months" }
+                    ] },
+                    "headerSubText": { "simpleText": "Gold" },
+                    "message": { "runs": [{ "text": "one year!" }] },
+                    "authorBadges": []
+                }))
+            } } },
+            { "addChatItemAction": { "item": {
+                "liveChatMembershipItemRenderer": with_base("upgrade", json!({
+                    "headerSubText": { "runs": [
+                        { "text": "Upgraded membership to " }, { "text": "Platinum" }, { "text": "!" }
+                    ] },
+                    "authorBadges": []
+                }))
+            } } }
+        ])));
+        let memberships: Vec<Membership> = chat_events
+            .into_iter()
+            .filter_map(|chat_event| chat_event.into_chat_item()?.membership)
+            .collect();
+        assert!(matches!(&memberships[..], [
+                Membership { kind: MembershipKind::New, tier: Some(new_tier), months: None, .. },
+                Membership { kind: MembershipKind::Milestone, tier: Some(milestone_tier), months: Some(12), message },
+                Membership { kind: MembershipKind::Upgrade, tier: Some(upgrade_tier), .. },
+            ] if new_tier == "Gold"
+                && milestone_tier == "Gold"
+                && matches!(&message[..], [MessageItem::Text(text)] if text == "one year!")
+                && upgrade_tier == "Platinum"));
+    }
+
+    #[test]
+    fn test_parse_ticker() {
+        let photo = json!({
+            "thumbnails": [{ "url": "https://yt4.ggpht.com/photo=s32", "width": 32, "height": 32 }]
+        });
+        let (chat_events, _) = parse_chat_events(response(json!([
+            { "addLiveChatTickerItemAction": {
+                "item": { "liveChatTickerPaidMessageItemRenderer": {
+                    "id": "ticker",
+                    "authorExternalChannelId": "UCxxxxxxxxxxxxxxxxxxxxxx",
+                    "authorPhoto": photo,
+                    "amount": { "simpleText": "$5.00" },
+                    "durationSec": 60,
+                    "fullDurationSec": 120,
+                    "showItemEndpoint": { "showLiveChatItemEndpoint": { "renderer": {
+                        "liveChatTextMessageRenderer": with_base("paid", json!({
+                            "message": { "runs": [{ "text": "thanks" }] }
+                        }))
+                    } } }
+                } },
+                "durationSec": "60"
+            } },
+            { "addLiveChatTickerItemAction": {
+                "item": { "liveChatTickerSponsorItemRenderer": {
+                    "id": "sponsor",
+                    "authorExternalChannelId": "UCxxxxxxxxxxxxxxxxxxxxxx",
+                    "sponsorPhoto": photo,
+                    "detailText": { "simpleText": "Member" },
+                    "durationSec": 300,
+                    "fullDurationSec": 300
+                } },
+                "durationSec": "300"
+            } }
+        ])));
+        assert!(matches!(&chat_events[..], [
+                ChatEvent::Ticker(paid),
+                ChatEvent::Ticker(sponsor),
+            ] if paid.kind == TickerKind::PaidMessage
+                && paid.amount.as_deref() == Some("$5.00")
+                && paid.duration == Duration::from_secs(60)
+                && paid.full_duration == Duration::from_secs(120)
+                && paid.chat_item_id.as_deref() == Some("paid")
+                && paid.author.name.as_deref() == Some("author")
+                && sponsor.kind == TickerKind::Sponsor
+                && sponsor.detail.as_deref() == Some("Member")
+                && sponsor.chat_item_id.is_none()));
+    }
+
+    #[test]
+    fn test_parse_partial_ticker_and_banner() {
+        let body = serde_json::to_vec(&json!({
+            "responseContext": {},
+            "continuationContents": { "liveChatContinuation": {
+                "continuations": [],
+                "actions": [
+                    { "addLiveChatTickerItemAction": { "item": {
+                        "liveChatTickerPaidMessageItemRenderer": {
+                            "id": "ticker",
+                            "authorExternalChannelId": "UCxxxxxxxxxxxxxxxxxxxxxx",
+                            "authorPhoto": { "thumbnails": [] }
+                        }
+                    } } },
+                    { "addBannerToLiveChatCommand": { "bannerRenderer": {
+                        "liveChatBannerRenderer": {}
+                    } } }
+                ]
+            } }
+        }))
+        .unwrap();
+        let (chat_events, _, _) =
+            parse_chat_events_from_slice(&body, &ParseOptions::default()).unwrap();
+        assert!(matches!(&chat_events[..], [
+                ChatEvent::Ticker(ticker),
+                ChatEvent::Banner(BannerEvent::Added(banner)),
+            ] if ticker.amount.is_none()
+                && ticker.duration == Duration::ZERO
+                && banner.chat_item.is_none()));
+    }
+
+    #[test]
+    fn test_parse_banner_poll_and_engagement() {
+        let poll = json!({ "pollRenderer": {
+            "liveChatPollId": "poll",
+            "header": { "pollHeaderRenderer": {
+                "pollQuestion": { "runs": [{ "text": "Cats or dogs?" }] },
+                "metadataText": { "runs": [{ "text": "@owner" }, { "text": " • " }, { "text": "3 votes" }] }
+            } },
+            "choices": [
+                { "text": { "runs": [{ "text": "Cats" }] }, "voteRatio": 0.6667,
+                  "votePercentage": { "simpleText": "67%" } },
+                { "text": { "runs": [{ "text": "Dogs" }] }, "voteRatio": 0.3333,
+                  "votePercentage": { "simpleText": "33%" } }
+            ]
+        } });
+        let (chat_events, _) = parse_chat_events(response(json!([
+            { "addBannerToLiveChatCommand": { "bannerRenderer": { "liveChatBannerRenderer": {
+                "header": { "liveChatBannerHeaderRenderer": {
+                    "text": { "runs": [{ "text": "Pinned by " }, { "text": "@owner" }] }
+                } },
+                "contents": {
+                    "liveChatTextMessageRenderer": with_base("pinned", json!({
+                        "message": { "runs": [{ "text": "welcome" }] }
+                    }))
+                },
+                "actionId": "banner"
+            } } } },
+            { "removeBannerForLiveChatCommand": { "targetActionId": "banner" } },
+            { "showLiveChatActionPanelAction": { "panelToShow": { "liveChatActionPanelRenderer": {
+                "id": "panel",
+                "contents": poll
+            } } } },
+            { "updateLiveChatPollAction": { "pollToUpdate": poll } },
+            { "closeLiveChatActionPanelAction": { "targetPanelId": "panel" } },
+            { "addChatItemAction": { "item": { "liveChatViewerEngagementMessageRenderer": {
+                "id": "notice",
+                "timestampUsec": "1676000000000000",
+                "icon": { "iconType": "YOUTUBE_ROUND" },
+                "message": { "runs": [{ "text": "Welcome to live chat!" }] }
+            } } } }
+        ])));
+        assert!(matches!(&chat_events[..], [
+                ChatEvent::Banner(BannerEvent::Added(banner)),
+                ChatEvent::Banner(BannerEvent::Removed { action_id }),
+                ChatEvent::Poll(PollEvent::Shown { panel_id, poll: shown }),
+                ChatEvent::Poll(PollEvent::Updated(_)),
+                ChatEvent::Poll(PollEvent::Closed { .. }),
+                ChatEvent::Engagement(Engagement { id, icon: Some(icon), .. }),
+            ] if banner.header.as_deref() == Some("Pinned by @owner")
+                && banner.chat_item.as_ref().is_some_and(|pinned| pinned.id == "pinned")
+                && action_id == "banner"
+                && panel_id == "panel"
+                && shown.question.as_deref() == Some("Cats or dogs?")
+                && shown.metadata.as_deref() == Some("@owner • 3 votes")
+                && matches!(&shown.choices[..], [cats, _]
+                    if cats.text == "Cats" && cats.vote_percentage.as_deref() == Some("67%"))
+                && id == "notice"
+                && icon == "YOUTUBE_ROUND"));
+    }
+
+    #[test]
+    fn test_parse_partial_poll_and_engagement() {
+        let (chat_events, _) = parse_chat_events(response(json!([
+            { "updateLiveChatPollAction": { "pollToUpdate": { "pollRenderer": {
+                "choices": [{ "voteRatio": 1.0 }]
+            } } } },
+            { "closeLiveChatActionPanelAction": {} },
+            { "addChatItemAction": { "item": { "liveChatViewerEngagementMessageRenderer": {
+                "icon": { "iconType": "POLL" }
+            } } } }
+        ])));
+        assert!(matches!(&chat_events[..], [
+                ChatEvent::Poll(PollEvent::Updated(poll)),
+                ChatEvent::Poll(PollEvent::Closed { .. }),
+                ChatEvent::Engagement(engagement),
+            ] if poll.question.is_none()
+                && matches!(&poll.choices[..], [choice] if choice.text.is_empty())
+                && engagement.message.is_empty()));
+    }
+
+    #[test]
+    fn test_parse_raid() {
+        let (chat_events, _) = parse_chat_events(response(json!([
+            { "addBannerToLiveChatCommand": { "bannerRenderer": { "liveChatBannerRenderer": {
+                "contents": { "liveChatBannerRedirectRenderer": {
+                    "bannerMessage": { "runs": [
+                        { "text": "@raider", "bold": true },
+                        { "text": " and their viewers just joined. Say hello!" }
+                    ] },
+                    "inlineActionButton": { "buttonRenderer": {
+                        "command": { "watchEndpoint": { "videoId": "abcdefghijk" } }
+                    } }
+                } },
+                "actionId": "raid"
+            } } } }
+        ])));
+        assert!(matches!(&chat_events[..], [ChatEvent::Raid(raid)]
+            if raid.action_id == "raid"
+                && raid.from_channel.as_deref() == Some("@raider")
+                && raid.to_video_id.as_deref() == Some("abcdefghijk")
+                && raid.text == "@raider and their viewers just joined. Say hello!"));
+    }
+
+    #[test]
+    fn test_parse_rich_text() {
+        let message = parse_message(
+            serde_json::from_value(json!([
+                { "text": "see " },
+                { "text": "example.com", "navigationEndpoint": { "urlEndpoint": {
+                    "url": "https://www.youtube.com/redirect?event=live_chat&q=https%3A%2F%2Fexample.com%2Fa%3Fb%3Dc"
+                } } },
+                { "text": " " },
+                { "text": "@someone", "navigationEndpoint": { "browseEndpoint": {
+                    "browseId": "UCxxxxxxxxxxxxxxxxxxxxxx"
+                } } },
+                { "text": "loud", "bold": true, "italics": true }
+            ]))
+            .unwrap(),
+        );
+        assert!(matches!(&message[..], [
+                MessageItem::Text(_),
+                MessageItem::Link { url, .. },
+                MessageItem::Text(_),
+                MessageItem::Mention { channel_id: Some(channel_id), .. },
+                MessageItem::StyledText { style: TextStyle { bold: true, italics: true }, .. },
+            ] if url == "https://example.com/a?b=c" && channel_id == "UCxxxxxxxxxxxxxxxxxxxxxx"));
+    }
+
+    #[test]
+    fn test_parse_chat_events_lenient() {
+        let data = json!({
+            "responseContext": {},
+            "continuationContents": { "liveChatContinuation": {
+                "continuations": [{
+                    "timedContinuationData": { "timeoutMs": 5000, "continuation": "next" }
+                }],
+                "actions": [
+                    { "addChatItemAction": { "item": {
+                        "liveChatTextMessageRenderer": with_base("a", json!({
+                            "message": { "runs": [{ "text": "hello" }] }
+                        }))
+                    } } },
+                    { "addChatItemAction": { "item": {
+                        "liveChatTextMessageRenderer": { "message": { "runs": [] } }
+                    } } },
+                    { "addChatItemAction": { "item": {
+                        "liveChatPlaceholderItemRenderer": { "id": "b" }
+                    } } }
+                ]
+            } }
+        });
+        let (chat_events, continuation) = parse_chat_events_lenient(data).unwrap();
+        assert_eq!(continuation.unwrap().token(), "next");
+        assert!(matches!(&chat_events[..], [
+                ChatEvent::Chat(_),
+                ChatEvent::Unknown(malformed),
+                ChatEvent::Unknown(unsupported),
+            ] if malformed.reason.starts_with("malformed `liveChatTextMessageRenderer`")
+                && unsupported.reason == "unsupported `liveChatPlaceholderItemRenderer` in `addChatItemAction`"
+                && unsupported.action["addChatItemAction"]["item"]["liveChatPlaceholderItemRenderer"]["id"] == "b"));
+    }
+
+    #[test]
+    fn test_parse_chat_events_from_slice() {
+        let renderer = with_base(
+            "a",
+            json!({
+                "message": { "runs": [{ "text": "say \"hi\" é" }] },
+                "authorBadges": [{ "liveChatAuthorBadgeRenderer": {
+                    "icon": { "iconType": "MODERATOR" },
+                    "tooltip": "Moderator",
+                    "accessibility": { "accessibilityData": { "label": "Moderator" } }
+                } }]
+            }),
+        );
+        let action = json!({ "addChatItemAction": { "item": {
+            "liveChatTextMessageRenderer": renderer.clone()
+        } } });
+        let mut data = serde_json::to_value(response(json!([]))).unwrap();
+        data["continuationContents"]["liveChatContinuation"]["actions"] = json!([action]);
+        let data = serde_json::to_vec(&data).unwrap();
+        let (chat_events, raw_actions, continuation) =
+            parse_chat_events_from_slice(&data, &ParseOptions::default()).unwrap();
+        assert_eq!(continuation.unwrap().token(), "next");
+        assert_eq!(
+            serde_json::from_str::<Value>(raw_actions[0].get()).unwrap(),
+            action
+        );
+        let [ChatEvent::Chat(chat_item)] = &chat_events[..] else {
+            panic!("unexpected events: {:?}", chat_events);
+        };
+        assert!(chat_item.is_moderator);
+        assert!(!chat_item.is_owner && !chat_item.is_verified);
+        assert!(
+            matches!(&chat_item.message[..], [MessageItem::Text(text)] if text == "say \"hi\" é")
+        );
+
+        let amount = json!({ "simpleText": "¥500" });
+        let renderers: Vec<Renderer> = vec![
+            Renderer::LiveChatPaidMessageRenderer(
+                serde_json::from_value(with_base("paid", json!({
+                    "message": { "runs": [{ "text": "thanks" }] },
+                    "purchaseAmountText": amount,
+                    "headerBackgroundColor": 4278239141u32, "headerTextColor": 4278190080u32,
+                    "bodyBackgroundColor": 4280150454u32, "bodyTextColor": 4278190080u32,
+                    "authorNameTextColor": 2315255808u32
+                })))
+                .unwrap(),
+            ),
+            Renderer::LiveChatPaidStickerRenderer(
+                serde_json::from_value(with_base("sticker", json!({
+                    "purchaseAmountText": amount,
+                    "sticker": {
+                        "thumbnails": [{ "url": "//example.com/sticker.png", "width": 40, "height": 40 }],
+                        "accessibility": { "accessibilityData": { "label": "a sticker" } }
+                    },
+                    "moneyChipBackgroundColor": 4280191205u32, "moneyChipTextColor": 4278190080u32,
+                    "stickerDisplayWidth": 40, "stickerDisplayHeight": 40,
+                    "BackgroundColor": 4279592384u32, "authorNameTextColor": 2315255808u32
+                })))
+                .unwrap(),
+            ),
+            Renderer::LiveChatMembershipItemRenderer(
+                serde_json::from_value(with_base("milestone", json!({
+                    "headerPrimaryText": { "runs": [{ "text": "Member for " }, { "text": "12" }, { "text": " months" }] },
+                    "headerSubText": { "simpleText": "Gold" },
+                    "message": { "runs": [{ "text": "a year already" }] },
+                    "authorBadges": []
+                })))
+                .unwrap(),
+            ),
+        ];
+        let chat_items: Vec<ChatItem> = renderers
+            .into_iter()
+            .map(Renderer::into_chat_item)
+            .collect();
+        assert!(matches!(&chat_items[..], [paid, sticker, milestone]
+            if paid.superchat.as_ref().is_some_and(|superchat| superchat.amount == "¥500" && superchat.sticker.is_none())
+                && matches!(&paid.message[..], [MessageItem::Text(text)] if text == "thanks")
+                && sticker.superchat.as_ref().is_some_and(|superchat| superchat.sticker.is_some())
+                && sticker.message.is_empty()
+                && milestone.membership.as_ref().is_some_and(|membership| membership.months == Some(12)
+                    && membership.tier.as_deref() == Some("Gold"))
+                && milestone.author.name.as_deref() == Some("author")));
+    }
+
+    #[derive(Deserialize)]
+    struct FooRenderer {
+        #[serde(flatten)]
+        base: crate::youtube_types::MessageRendererBase,
+        text: String,
+    }
+
+    impl ChatRenderer for FooRenderer {
+        fn id(&self) -> String {
+            self.base.id.clone()
+        }
+        fn author_name(&self) -> Option<String> {
+            self.base.author_name.clone().map(|name| name.simple_text)
+        }
+        fn channel_id(&self) -> String {
+            self.base.author_external_channel_id.clone()
+        }
+        fn thumbnails(&self) -> Vec<Thumbnail> {
+            self.base.author_photo.thumbnails.clone()
+        }
+        fn runs(&self) -> Vec<MessageRun> {
+            serde_json::from_value(json!([{ "text": self.text }])).unwrap()
+        }
+        fn time_stamp(&self) -> Option<DateTime<Utc>> {
+            parse_timestamp_usec(&self.base.timestamp_usec)
+        }
+    }
+
+    #[test]
+    fn test_registered_renderer() {
+        let mut options = ParseOptions::default();
+        options
+            .renderers
+            .register::<FooRenderer>("liveChatFooRenderer", ChatEvent::Chat);
+        let mut data = serde_json::to_value(response(json!([]))).unwrap();
+        data["continuationContents"]["liveChatContinuation"]["actions"] = json!([
+            { "addChatItemAction": { "item": {
+                "liveChatFooRenderer": with_base("foo", json!({ "text": "custom" }))
+            } } }
+        ]);
+        let body = serde_json::to_vec(&data).unwrap();
+        let (from_slice, _, _) = parse_chat_events_from_slice(&body, &options).unwrap();
+        let (chat_events, _) = parse_chat_events_with(data, &options).unwrap();
+        for chat_events in [chat_events, from_slice] {
+            assert!(matches!(&chat_events[..], [ChatEvent::Chat(chat_item)]
+                if chat_item.id == "foo"
+                    && matches!(&chat_item.message[..], [MessageItem::Text(text)] if text == "custom")));
+        }
+    }
+
+    #[test]
+    fn test_live_page_interstitials() {
+        let error = |page: &str| {
+            get_options_from_live_page(page.to_string())
+                .unwrap_err()
+                .downcast_ref::<LivePageError>()
+                .copied()
+        };
+        let consent = r#"<form action="https://consent.youtube.com/save" method="POST">"#;
+        assert_eq!(error(consent), Some(LivePageError::ConsentRequired));
+        let bot_check = r#"<link rel="canonical" href="https://www.youtube.com/watch?v=jfKfPfyJRdk">
+            {"playabilityStatus":{"status":"LOGIN_REQUIRED","reason":"Sign in to confirm you\u2019re not a bot"}}"#;
+        assert_eq!(error(bot_check), Some(LivePageError::BotCheck));
+        let sorry = "Our systems have detected unusual traffic from your computer network.";
+        assert_eq!(error(sorry), Some(LivePageError::BotCheck));
+        assert_eq!(error("<html></html>"), None);
+
+        // A live page quoting the phrases is still a live page.
+        let live = r#"<link rel="canonical" href="https://www.youtube.com/watch?v=jfKfPfyJRdk">
+            <title>How to confirm you're not a bot - YouTube</title>
+            <a href="https://consent.youtube.com/m">cookies</a>
+            {"INNERTUBE_API_KEY":"key","clientVersion":"2.20230208.00.00","continuation":"next"}"#;
+        let (options, live_id) = get_options_from_live_page(live.to_string()).unwrap();
+        assert_eq!(live_id, "jfKfPfyJRdk");
+        assert_eq!(options.continuation, "next");
+    }
+}
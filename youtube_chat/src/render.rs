@@ -0,0 +1,305 @@
+use crate::item::{ChatItem, EmojiItem, MessageItem, TextStyle};
+
+/// How [`ChatItem::to_plain_text`] writes emojis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmojiStyle {
+    /// `:shortcode:` for every emoji.
+    Shortcode,
+    /// The Unicode character for standard emojis, `:shortcode:` for custom ones.
+    Unicode,
+}
+
+impl ChatItem {
+    pub fn to_plain_text(&self, emoji_style: EmojiStyle) -> String {
+        self.message
+            .iter()
+            .map(|message_item| match message_item {
+                MessageItem::Emoji(emoji) => plain_emoji(emoji, emoji_style),
+                message_item => text_of(message_item).to_string(),
+            })
+            .collect()
+    }
+
+    /// Renders the message as HTML safe to embed in a page. Custom emojis become
+    /// `<img>` tags and only `http(s)` links are kept.
+    pub fn to_html(&self) -> String {
+        self.message.iter().map(html_item).collect()
+    }
+
+    pub fn to_markdown(&self) -> String {
+        self.message.iter().map(markdown_item).collect()
+    }
+}
+
+fn text_of(message_item: &MessageItem) -> &str {
+    match message_item {
+        MessageItem::Text(text)
+        | MessageItem::StyledText { text, .. }
+        | MessageItem::Link { text, .. }
+        | MessageItem::Mention { text, .. } => text,
+        MessageItem::Emoji(emoji) => emoji.emoji_text.as_deref().unwrap_or_default(),
+    }
+}
+
+fn shortcode(emoji: &EmojiItem) -> Option<&str> {
+    emoji.image_item.as_ref()?.alt.as_deref()
+}
+
+fn plain_emoji(emoji: &EmojiItem, emoji_style: EmojiStyle) -> String {
+    let text = match emoji_style {
+        EmojiStyle::Shortcode => shortcode(emoji).or(emoji.emoji_text.as_deref()),
+        EmojiStyle::Unicode => emoji.emoji_text.as_deref().or(shortcode(emoji)),
+    };
+    text.unwrap_or_default().to_string()
+}
+
+fn is_http_url(url: &str) -> bool {
+    url.starts_with("https://") || url.starts_with("http://")
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn html_style(html: String, style: TextStyle) -> String {
+    let html = if style.italics {
+        format!("<i>{}</i>", html)
+    } else {
+        html
+    };
+    if style.bold {
+        format!("<b>{}</b>", html)
+    } else {
+        html
+    }
+}
+
+fn html_item(message_item: &MessageItem) -> String {
+    match message_item {
+        MessageItem::Text(text) => escape_html(text),
+        MessageItem::StyledText { text, style } => html_style(escape_html(text), *style),
+        MessageItem::Link { text, url, style } if is_http_url(url) => html_style(
+            format!(
+                r#"<a href="{}" rel="nofollow noopener" target="_blank">{}</a>"#,
+                escape_html(url),
+                escape_html(text)
+            ),
+            *style,
+        ),
+        MessageItem::Link { text, style, .. } => html_style(escape_html(text), *style),
+        MessageItem::Mention { text, style, .. } => html_style(
+            format!(r#"<span class="mention">{}</span>"#, escape_html(text)),
+            *style,
+        ),
+        MessageItem::Emoji(emoji) => match &emoji.image_item {
+            Some(image) if emoji.is_custome_emoji == Some(true) && is_http_url(&image.url) => {
+                format!(
+                    r#"<img class="emoji" src="{}" alt="{}">"#,
+                    escape_html(&image.url),
+                    escape_html(image.alt.as_deref().unwrap_or_default())
+                )
+            }
+            _ => escape_html(&plain_emoji(emoji, EmojiStyle::Unicode)),
+        },
+    }
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '(' | ')' | '<' | '>' | '#' | '!' | '|' | '~'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Escapes the characters that would end a Markdown link destination.
+fn escape_markdown_url(url: &str) -> String {
+    url.replace('(', "%28")
+        .replace(')', "%29")
+        .replace(' ', "%20")
+}
+
+/// Wraps `markdown` in emphasis markers, leaving surrounding whitespace outside
+/// since `** text**` is not parsed as bold.
+fn markdown_style(markdown: String, style: TextStyle) -> String {
+    let marker = match (style.bold, style.italics) {
+        (true, true) => "***",
+        (true, false) => "**",
+        (false, true) => "*",
+        (false, false) => return markdown,
+    };
+    let trimmed = markdown.trim();
+    if trimmed.is_empty() {
+        return markdown;
+    }
+    let start = markdown.len() - markdown.trim_start().len();
+    let end = start + trimmed.len();
+    format!(
+        "{}{}{}{}{}",
+        &markdown[..start],
+        marker,
+        trimmed,
+        marker,
+        &markdown[end..]
+    )
+}
+
+fn markdown_item(message_item: &MessageItem) -> String {
+    match message_item {
+        MessageItem::Text(text) => escape_markdown(text),
+        MessageItem::StyledText { text, style } => markdown_style(escape_markdown(text), *style),
+        MessageItem::Link { text, url, style } if is_http_url(url) => markdown_style(
+            format!("[{}]({})", escape_markdown(text), escape_markdown_url(url)),
+            *style,
+        ),
+        MessageItem::Link { text, style, .. } => markdown_style(escape_markdown(text), *style),
+        MessageItem::Mention {
+            text,
+            channel_id: Some(channel_id),
+            style,
+        } => markdown_style(
+            format!(
+                "[{}](https://www.youtube.com/channel/{})",
+                escape_markdown(text),
+                escape_markdown_url(channel_id)
+            ),
+            *style,
+        ),
+        MessageItem::Mention { text, style, .. } => markdown_style(escape_markdown(text), *style),
+        MessageItem::Emoji(emoji) => match &emoji.image_item {
+            Some(image) if emoji.is_custome_emoji == Some(true) && is_http_url(&image.url) => {
+                format!(
+                    "![{}]({})",
+                    escape_markdown(image.alt.as_deref().unwrap_or_default()),
+                    escape_markdown_url(&image.url)
+                )
+            }
+            _ => escape_markdown(&plain_emoji(emoji, EmojiStyle::Unicode)),
+        },
+    }
+}
+
+#[cfg(test)]
+mod render_tests {
+    use super::*;
+    use crate::item::{Author, ImageItem};
+
+    fn chat_item(message: Vec<MessageItem>) -> ChatItem {
+        ChatItem {
+            id: "id".to_string(),
+            author: Author {
+                name: None,
+                thumbnail: None,
+                channel_id: "UCxxxxxxxxxxxxxxxxxxxxxx".to_string(),
+                badge: None,
+            },
+            message,
+            superchat: None,
+            membership: None,
+            is_membership: false,
+            is_verified: false,
+            is_owner: false,
+            is_moderator: false,
+            timestamp: None,
+        }
+    }
+
+    fn emoji(emoji_text: &str, shortcut: &str, is_custom: bool) -> MessageItem {
+        MessageItem::Emoji(EmojiItem {
+            image_item: Some(ImageItem {
+                url: "https://yt3.ggpht.com/emoji=w24-h24".to_string(),
+                alt: Some(shortcut.to_string()),
+                sizes: Vec::new(),
+            }),
+            emoji_text: Some(emoji_text.to_string()),
+            is_custome_emoji: Some(is_custom),
+            emoji_id: None,
+            shortcuts: vec![shortcut.to_string()],
+            search_terms: Vec::new(),
+            supports_skin_tone: None,
+        })
+    }
+
+    fn sample() -> ChatItem {
+        chat_item(vec![
+            MessageItem::Text("1 < 2 & *not bold* ".to_string()),
+            emoji("😀", ":grinning_face:", false),
+            emoji(":_custom:", ":_custom:", true),
+            MessageItem::StyledText {
+                text: " loud ".to_string(),
+                style: TextStyle {
+                    bold: true,
+                    italics: false,
+                },
+            },
+            MessageItem::Link {
+                text: "site".to_string(),
+                url: "https://example.com/a_(b)".to_string(),
+                style: TextStyle::default(),
+            },
+            MessageItem::Link {
+                text: "bad".to_string(),
+                url: "javascript:alert(1)".to_string(),
+                style: TextStyle::default(),
+            },
+        ])
+    }
+
+    #[test]
+    fn test_to_plain_text() {
+        let chat_item = sample();
+        assert_eq!(
+            chat_item.to_plain_text(EmojiStyle::Unicode),
+            "1 < 2 & *not bold* 😀:_custom: loud sitebad"
+        );
+        assert_eq!(
+            chat_item.to_plain_text(EmojiStyle::Shortcode),
+            "1 < 2 & *not bold* :grinning_face::_custom: loud sitebad"
+        );
+    }
+
+    #[test]
+    fn test_to_html() {
+        assert_eq!(
+            sample().to_html(),
+            concat!(
+                "1 &lt; 2 &amp; *not bold* 😀",
+                r#"<img class="emoji" src="https://yt3.ggpht.com/emoji=w24-h24" alt=":_custom:">"#,
+                "<b> loud </b>",
+                r#"<a href="https://example.com/a_(b)" rel="nofollow noopener" target="_blank">site</a>"#,
+                "bad"
+            )
+        );
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        assert_eq!(
+            sample().to_markdown(),
+            concat!(
+                r"1 \< 2 & \*not bold\* 😀",
+                r"![:\_custom:](https://yt3.ggpht.com/emoji=w24-h24)",
+                " **loud** ",
+                "[site](https://example.com/a_%28b%29)",
+                "bad"
+            )
+        );
+    }
+}
@@ -1,21 +1,126 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
 
+use crate::{
+    diagnostics::SchemaDriftReport,
+    item::{ChatEvent, Continuation},
+    parser::{parse_slice, ParseOptions},
+    youtube_types::GetLiveChatBody,
+};
+#[cfg(any(feature = "reqwest", feature = "isahc", feature = "wasm"))]
 use crate::{
     item::ChatItem,
     parser::{get_options_from_live_page, parse_chat_data},
-    youtube_types::{GetLiveChatBody, GetLiveChatResponse},
+    youtube_types::GetLiveChatResponse,
 };
 
-#[derive(Clone, Serialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct RequestOptions {
     pub api_key: String,
     pub client_version: String,
     pub continuation: String,
 }
 
-pub async fn fetch_chat<'a>(
+/// Language, region and HTTP details sent with every request of a client, so that
+/// localized strings such as membership headers, amounts and system messages are predictable.
+#[derive(Clone, Debug, Default)]
+pub struct ClientContext {
+    /// `hl`, e.g. `en` or `ja`, [`DEFAULT_LANGUAGE`] when not set.
+    pub language: Option<String>,
+    /// `gl`, e.g. `US` or `JP`.
+    pub region: Option<String>,
+    /// IANA time zone, e.g. `Asia/Tokyo`.
+    pub time_zone: Option<String>,
+    pub user_agent: Option<String>,
+    /// Sent in the `Cookie` header, e.g. [`CONSENT_COOKIES`].
+    pub cookies: Vec<(String, String)>,
+    /// Sent after `User-Agent`, `Accept-Language` and `Cookie`.
+    pub headers: Vec<(String, String)>,
+}
+
+/// `hl` of requests without [`ClientContext::language`]. Membership upgrades are only
+/// recognised in English, see [`crate::item::MembershipKind::Upgrade`].
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// Cookies of a visitor who rejected the optional cookies on the consent page, which
+/// YouTube shows instead of the live page in the EEA and the UK without them.
+pub const CONSENT_COOKIES: &[(&str, &str)] = &[("SOCS", "CAI")];
+
+impl ClientContext {
+    /// `User-Agent`, `Accept-Language` made of `language` and `region`, `Cookie`, then `headers`.
+    pub fn http_headers(&self) -> Vec<(String, String)> {
+        let accept_language = match (&self.language, &self.region) {
+            (Some(language), Some(region)) => Some(format!("{}-{}", language, region)),
+            (Some(language), None) => Some(language.clone()),
+            (None, _) => None,
+        };
+        let user_agent = self.user_agent.clone().map(|value| ("User-Agent", value));
+        let accept_language = accept_language.map(|value| ("Accept-Language", value));
+        let cookie = (!self.cookies.is_empty()).then(|| {
+            let cookies: Vec<String> = self
+                .cookies
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect();
+            ("Cookie", cookies.join("; "))
+        });
+        user_agent
+            .into_iter()
+            .chain(accept_language)
+            .chain(cookie)
+            .map(|(name, value)| (name.to_string(), value))
+            .chain(self.headers.iter().cloned())
+            .collect()
+    }
+}
+
+/// Chat events of a response, the raw JSON of the actions they came from and
+/// where to continue from. `R` is `&RawValue` when the JSON is borrowed from the body.
+pub type ChatEventBatch<R = Box<RawValue>> = (Vec<ChatEvent>, Vec<R>, Option<Continuation>);
+
+#[cfg(any(feature = "reqwest", feature = "isahc", feature = "wasm"))]
+pub async fn fetch_chat(options: RequestOptions) -> Result<(Vec<ChatItem>, String), anyhow::Error> {
+    let body = post_get_live_chat(options, &ClientContext::default(), None).await?;
+    let json: GetLiveChatResponse = serde_json::from_slice(body.as_ref())?;
+    Ok(parse_chat_data(json))
+}
+
+/// Fetches the next chat events together with the raw JSON of every action they came from.
+/// With `drift`, the response is also inspected for JSON that `youtube_types` does not model.
+#[cfg(any(feature = "reqwest", feature = "isahc", feature = "wasm"))]
+pub async fn fetch_chat_events(
     options: RequestOptions,
-) -> Result<(Vec<ChatItem>, String), anyhow::Error> {
+    parse_options: &ParseOptions,
+    drift: Option<&mut SchemaDriftReport>,
+) -> Result<ChatEventBatch, anyhow::Error> {
+    let body = post_get_live_chat(options, &ClientContext::default(), None).await?;
+    parse_get_live_chat(body.as_ref(), parse_options, drift).map(into_owned)
+}
+
+/// Parses a `get_live_chat` response body like [`fetch_chat_events`] does,
+/// for callers that send the request themselves. The raw JSON of the actions
+/// is borrowed from `body`.
+pub fn parse_get_live_chat<'a>(
+    body: &'a [u8],
+    parse_options: &ParseOptions,
+    drift: Option<&mut SchemaDriftReport>,
+) -> Result<ChatEventBatch<&'a RawValue>, anyhow::Error> {
+    Ok(parse_slice(body, parse_options, drift)?)
+}
+
+pub(crate) fn into_owned(
+    (chat_events, raw_actions, continuation): ChatEventBatch<&RawValue>,
+) -> ChatEventBatch {
+    let raw_actions = raw_actions.into_iter().map(RawValue::to_owned).collect();
+    (chat_events, raw_actions, continuation)
+}
+
+/// URL and JSON body of the `get_live_chat` request for `options`, to send it
+/// with another HTTP client together with [`ClientContext::http_headers`].
+pub fn get_live_chat_request(
+    options: RequestOptions,
+    context: &ClientContext,
+) -> (String, GetLiveChatBody) {
     let url = format!(
         "https://www.youtube.com/youtubei/v1/live_chat/get_live_chat?key={}",
         options.api_key
@@ -24,16 +129,233 @@ pub async fn fetch_chat<'a>(
         options.continuation,
         options.client_version,
         "WEB".to_string(),
-    );
-    let client = reqwest::Client::new();
-    let response = client.post(url).json(&body).send().await?;
-    let text = response.text().await.unwrap();
-    let json: GetLiveChatResponse = serde_json::from_str(&text)?;
-    Ok(parse_chat_data(json))
+    )
+    .with_context(context);
+    (url, body)
+}
+
+#[cfg(any(feature = "reqwest", feature = "isahc", feature = "wasm"))]
+pub(crate) async fn post_get_live_chat(
+    options: RequestOptions,
+    context: &ClientContext,
+    proxy: Option<&str>,
+) -> Result<impl AsRef<[u8]>, anyhow::Error> {
+    let (url, body) = get_live_chat_request(options, context);
+    http::post_json(url, &body, &context.http_headers(), proxy).await
 }
 
+#[cfg(any(feature = "reqwest", feature = "isahc", feature = "wasm"))]
 pub async fn fetch_live_page(url: String) -> Result<(RequestOptions, String), anyhow::Error> {
-    let client = reqwest::Client::new();
-    let response = client.get(url).send().await?.text().await?;
+    fetch_live_page_through(url, &ClientContext::default(), None).await
+}
+
+/// Like [`fetch_live_page`], with the headers of `context` and through `proxy` if given.
+#[cfg(any(feature = "reqwest", feature = "isahc", feature = "wasm"))]
+pub(crate) async fn fetch_live_page_through(
+    url: String,
+    context: &ClientContext,
+    proxy: Option<&str>,
+) -> Result<(RequestOptions, String), anyhow::Error> {
+    let response = http::get_text(url, &context.http_headers(), proxy).await?;
     get_options_from_live_page(response)
 }
+
+/// Requests of the async API on reqwest, which needs tokio.
+#[cfg(all(feature = "reqwest", not(any(feature = "isahc", feature = "wasm"))))]
+mod http {
+    use reqwest::RequestBuilder;
+    use serde::Serialize;
+
+    fn client(proxy: Option<&str>) -> Result<reqwest::Client, anyhow::Error> {
+        let mut builder = reqwest::Client::builder();
+        if let Some(proxy) = proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+        }
+        Ok(builder.build()?)
+    }
+
+    fn with_headers(mut request: RequestBuilder, headers: &[(String, String)]) -> RequestBuilder {
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        request
+    }
+
+    pub(super) async fn post_json(
+        url: String,
+        body: &impl Serialize,
+        headers: &[(String, String)],
+        proxy: Option<&str>,
+    ) -> Result<bytes::Bytes, anyhow::Error> {
+        let request = with_headers(client(proxy)?.post(url), headers);
+        let response = request.json(body).send().await?;
+        Ok(response.error_for_status()?.bytes().await?)
+    }
+
+    pub(super) async fn get_text(
+        url: String,
+        headers: &[(String, String)],
+        proxy: Option<&str>,
+    ) -> Result<String, anyhow::Error> {
+        let response = with_headers(client(proxy)?.get(url), headers)
+            .send()
+            .await?;
+        Ok(response.error_for_status()?.text().await?)
+    }
+}
+
+/// Requests of the async API on isahc, which runs on any executor.
+#[cfg(all(feature = "isahc", not(feature = "wasm")))]
+mod http {
+    use anyhow::ensure;
+    use isahc::{config::Configurable, http::request::Builder, AsyncReadResponseExt, Request};
+    use serde::Serialize;
+
+    fn prepare(
+        mut builder: Builder,
+        headers: &[(String, String)],
+        proxy: Option<&str>,
+    ) -> Result<Builder, anyhow::Error> {
+        for (name, value) in headers {
+            builder = builder.header(name, value);
+        }
+        Ok(match proxy {
+            Some(proxy) => builder.proxy(Some(proxy.parse()?)),
+            None => builder,
+        })
+    }
+
+    async fn send(request: Request<Vec<u8>>) -> Result<Vec<u8>, anyhow::Error> {
+        let mut response = isahc::send_async(request).await?;
+        ensure!(
+            response.status().is_success(),
+            "HTTP status {}",
+            response.status()
+        );
+        Ok(response.bytes().await?)
+    }
+
+    pub(super) async fn post_json(
+        url: String,
+        body: &impl Serialize,
+        headers: &[(String, String)],
+        proxy: Option<&str>,
+    ) -> Result<Vec<u8>, anyhow::Error> {
+        let request = prepare(Request::post(url), headers, proxy)?
+            .header("Content-Type", "application/json")
+            .body(serde_json::to_vec(body)?)?;
+        send(request).await
+    }
+
+    pub(super) async fn get_text(
+        url: String,
+        headers: &[(String, String)],
+        proxy: Option<&str>,
+    ) -> Result<String, anyhow::Error> {
+        let request = prepare(Request::get(url), headers, proxy)?.body(Vec::new())?;
+        Ok(String::from_utf8(send(request).await?)?)
+    }
+}
+
+/// Requests of the async API on the browser's `fetch`, which always uses the
+/// browser's proxy settings and may drop headers such as `User-Agent`.
+#[cfg(feature = "wasm")]
+mod http {
+    use anyhow::ensure;
+    use gloo_net::http::{Request, RequestBuilder, Response};
+    use serde::Serialize;
+
+    fn no_proxy(proxy: Option<&str>) -> Result<(), anyhow::Error> {
+        ensure!(
+            proxy.is_none(),
+            "fetch cannot send requests through a proxy"
+        );
+        Ok(())
+    }
+
+    fn check_status(response: Response) -> Result<Response, anyhow::Error> {
+        ensure!(response.ok(), "HTTP status {}", response.status());
+        Ok(response)
+    }
+
+    fn with_headers(mut request: RequestBuilder, headers: &[(String, String)]) -> RequestBuilder {
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        request
+    }
+
+    pub(super) async fn post_json(
+        url: String,
+        body: &impl Serialize,
+        headers: &[(String, String)],
+        proxy: Option<&str>,
+    ) -> Result<Vec<u8>, anyhow::Error> {
+        no_proxy(proxy)?;
+        let response = with_headers(Request::post(&url), headers)
+            .header("Content-Type", "application/json")
+            .body(serde_json::to_string(body)?)?
+            .send()
+            .await?;
+        Ok(check_status(response)?.binary().await?)
+    }
+
+    pub(super) async fn get_text(
+        url: String,
+        headers: &[(String, String)],
+        proxy: Option<&str>,
+    ) -> Result<String, anyhow::Error> {
+        no_proxy(proxy)?;
+        let response = with_headers(Request::get(&url), headers).send().await?;
+        Ok(check_status(response)?.text().await?)
+    }
+}
+
+#[cfg(test)]
+mod request_tests {
+    use super::*;
+
+    #[test]
+    fn test_client_context() {
+        let options = RequestOptions {
+            api_key: "key".to_string(),
+            client_version: "2.20230301.09.00".to_string(),
+            continuation: "token".to_string(),
+        };
+        let (_, body) = get_live_chat_request(options.clone(), &ClientContext::default());
+        let json = serde_json::to_value(body).unwrap();
+        assert_eq!(
+            json["context"]["client"],
+            serde_json::json!({ "clientVersion": "2.20230301.09.00", "clientName": "WEB", "hl": "en" })
+        );
+
+        let context = ClientContext {
+            language: Some("ja".to_string()),
+            region: Some("JP".to_string()),
+            time_zone: Some("Asia/Tokyo".to_string()),
+            user_agent: Some("collector/1.0".to_string()),
+            cookies: vec![("SOCS".to_string(), "CAI".to_string())],
+            headers: vec![("X-Deployment".to_string(), "tokyo-1".to_string())],
+        };
+        let (_, body) = get_live_chat_request(options, &context);
+        let json = serde_json::to_value(body).unwrap();
+        assert_eq!(json["context"]["client"]["hl"], "ja");
+        assert_eq!(json["context"]["client"]["gl"], "JP");
+        assert_eq!(json["context"]["client"]["timeZone"], "Asia/Tokyo");
+        assert_eq!(json["context"]["client"]["userAgent"], "collector/1.0");
+        let headers = context.http_headers();
+        let headers: Vec<(&str, &str)> = headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+        assert_eq!(
+            headers,
+            vec![
+                ("User-Agent", "collector/1.0"),
+                ("Accept-Language", "ja-JP"),
+                ("Cookie", "SOCS=CAI"),
+                ("X-Deployment", "tokyo-1"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,15 @@
+//! Waiting between requests without tying the async client to one runtime.
+
+use std::time::Duration;
+
+/// Waits for `duration` with the timer of the HTTP backend in use: `setTimeout` with
+/// `wasm`, async-io with `isahc`, which works with async-std, smol and other executors,
+/// otherwise tokio.
+pub async fn sleep(duration: Duration) {
+    #[cfg(feature = "wasm")]
+    gloo_timers::future::sleep(duration).await;
+    #[cfg(all(feature = "isahc", not(feature = "wasm")))]
+    async_io::Timer::after(duration).await;
+    #[cfg(not(any(feature = "isahc", feature = "wasm")))]
+    tokio::time::sleep(duration).await;
+}
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::request::{ClientContext, DEFAULT_LANGUAGE};
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct GetLiveChatBody {
     context: GetLiveChatBodyContext,
@@ -13,11 +15,26 @@ impl GetLiveChatBody {
                 client: GetLiveChatBodyContextClient {
                     client_version,
                     client_name,
+                    hl: None,
+                    gl: None,
+                    time_zone: None,
+                    user_agent: None,
                 },
             },
             continuation,
         }
     }
+
+    /// Sets `hl`, `gl`, `timeZone` and `userAgent` of the client from `context`.
+    pub fn with_context(mut self, context: &ClientContext) -> Self {
+        let client = &mut self.context.client;
+        let language = context.language.as_deref().unwrap_or(DEFAULT_LANGUAGE);
+        client.hl = Some(language.to_string());
+        client.gl = context.region.clone();
+        client.time_zone = context.time_zone.clone();
+        client.user_agent = context.user_agent.clone();
+        self
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -31,27 +48,37 @@ pub struct GetLiveChatBodyContextClient {
     client_version: String,
     #[serde(rename = "clientName")]
     client_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hl: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gl: Option<String>,
+    #[serde(rename = "timeZone", skip_serializing_if = "Option::is_none")]
+    time_zone: Option<String>,
+    #[serde(rename = "userAgent", skip_serializing_if = "Option::is_none")]
+    user_agent: Option<String>,
 }
 
+/// `A` is the type of each action, e.g. `&RawValue` to leave actions unparsed
+/// and borrowed from the response body.
 #[derive(Serialize, Deserialize, Debug)]
-pub struct GetLiveChatResponse {
+pub struct GetLiveChatResponse<A = Action> {
     #[serde(rename = "responseContext")]
     pub response_context: serde_json::Value,
     #[serde(rename = "trackingParams")]
     pub tracking_params: Option<String>,
     #[serde(rename = "continuationContents")]
-    pub continuation_contents: GetLiveChatResponseContinuationContents,
+    pub continuation_contents: GetLiveChatResponseContinuationContents<A>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-pub struct GetLiveChatResponseContinuationContents {
+pub struct GetLiveChatResponseContinuationContents<A = Action> {
     #[serde(rename = "liveChatContinuation")]
-    pub live_chat_continuaton: LiveChatContinuation,
+    pub live_chat_continuaton: LiveChatContinuation<A>,
 }
 #[derive(Serialize, Deserialize, Debug)]
-pub struct LiveChatContinuation {
+pub struct LiveChatContinuation<A = Action> {
     pub continuations: Vec<Continuation>,
-    pub actions: Option<Vec<Action>>,
+    pub actions: Option<Vec<A>>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -60,6 +87,12 @@ pub struct Continuation {
     pub invalidation_continuation_data: Option<InvalidationContinuationData>,
     #[serde(rename = "timedContinuationData")]
     pub timed_continuation_data: Option<TimedContinuationData>,
+    #[serde(rename = "reloadContinuationData")]
+    pub reload_continuation_data: Option<ReloadContinuationData>,
+    #[serde(rename = "liveChatReplayContinuationData")]
+    pub live_chat_replay_continuation_data: Option<LiveChatReplayContinuationData>,
+    #[serde(rename = "playerSeekContinuationData")]
+    pub player_seek_continuation_data: Option<PlayerSeekContinuationData>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -92,11 +125,58 @@ pub struct TimedContinuationData {
     pub click_tracking_params: Option<String>,
 }
 #[derive(Serialize, Deserialize, Debug)]
+pub struct ReloadContinuationData {
+    pub continuation: String,
+    #[serde(rename = "clickTrackingParams")]
+    pub click_tracking_params: Option<String>,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LiveChatReplayContinuationData {
+    #[serde(rename = "timeUntilLastMessageMsec")]
+    pub time_until_last_message_msec: Option<u64>,
+    pub continuation: String,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PlayerSeekContinuationData {
+    pub continuation: String,
+}
+#[derive(Serialize, Deserialize, Debug)]
 pub struct Action {
+    #[serde(rename = "clickTrackingParams")]
+    pub click_tracking_params: Option<String>,
     #[serde(rename = "addChatItemAction")]
     pub add_chat_item_action: Option<AddChatItemAction>,
     #[serde(rename = "addLiveChatTickerItemAction")]
-    pub add_live_chat_ticker_item_action: Option<serde_json::Value>,
+    pub add_live_chat_ticker_item_action: Option<AddLiveChatTickerItemAction>,
+    #[serde(rename = "markChatItemAsDeletedAction")]
+    pub mark_chat_item_as_deleted_action: Option<MarkChatItemAsDeletedAction>,
+    #[serde(rename = "markChatItemsByAuthorAsDeletedAction")]
+    pub mark_chat_items_by_author_as_deleted_action: Option<MarkChatItemsByAuthorAsDeletedAction>,
+    #[serde(rename = "addBannerToLiveChatCommand")]
+    pub add_banner_to_live_chat_command: Option<AddBannerToLiveChatCommand>,
+    #[serde(rename = "removeBannerForLiveChatCommand")]
+    pub remove_banner_for_live_chat_command: Option<RemoveBannerForLiveChatCommand>,
+    #[serde(rename = "showLiveChatActionPanelAction")]
+    pub show_live_chat_action_panel_action: Option<ShowLiveChatActionPanelAction>,
+    #[serde(rename = "updateLiveChatPollAction")]
+    pub update_live_chat_poll_action: Option<UpdateLiveChatPollAction>,
+    #[serde(rename = "closeLiveChatActionPanelAction")]
+    pub close_live_chat_action_panel_action: Option<CloseLiveChatActionPanelAction>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MarkChatItemAsDeletedAction {
+    #[serde(rename = "deletedStateMessage")]
+    pub deleted_state_message: Option<Message>,
+    #[serde(rename = "targetItemId")]
+    pub target_item_id: String,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MarkChatItemsByAuthorAsDeletedAction {
+    #[serde(rename = "deletedStateMessage")]
+    pub deleted_state_message: Option<Message>,
+    #[serde(rename = "externalChannelId")]
+    pub external_channel_id: String,
 }
 
 /* MessageRun */
@@ -105,6 +185,10 @@ pub struct Action {
 pub enum MessageRun {
     MessageText {
         text: String,
+        bold: Option<bool>,
+        italics: Option<bool>,
+        #[serde(rename = "navigationEndpoint")]
+        navigation_endpoint: Option<NavigationEndpoint>,
     },
     MessageEmoji {
         emoji: Emoji,
@@ -115,6 +199,27 @@ pub enum MessageRun {
     },
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NavigationEndpoint {
+    #[serde(rename = "urlEndpoint")]
+    pub url_endpoint: Option<UrlEndpoint>,
+    #[serde(rename = "browseEndpoint")]
+    pub browse_endpoint: Option<BrowseEndpoint>,
+    #[serde(rename = "watchEndpoint")]
+    pub watch_endpoint: Option<WatchEndpoint>,
+}
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UrlEndpoint {
+    pub url: String,
+}
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BrowseEndpoint {
+    #[serde(rename = "browseId")]
+    pub browse_id: String,
+    #[serde(rename = "canonicalBaseUrl")]
+    pub canonical_base_url: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Emoji {
     #[serde(rename = "emojiId")]
@@ -125,6 +230,8 @@ pub struct Emoji {
     #[serde(rename = "supportsSkinTone")]
     pub supports_skin_tone: Option<bool>,
     pub image: Image,
+    #[serde(rename = "isCustomEmoji")]
+    pub is_custom_emoji: Option<bool>,
 }
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Image {
@@ -231,6 +338,9 @@ pub struct LiveChatTextMessageRenderer {
     #[serde(flatten)]
     pub message_renderer_base: MessageRendererBase,
     pub message: Message,
+    /// Keys not modelled above, which `flatten` would otherwise drop silently.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -273,6 +383,9 @@ pub struct LiveChatPaidStickerRenderer {
     pub background_color: isize,
     #[serde(rename = "authorNameTextColor")]
     pub author_name_text_color: isize,
+    /// Keys not modelled above, which `flatten` would otherwise drop silently.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Sticker {
@@ -289,14 +402,22 @@ pub struct PurchaseAmountText {
 pub struct LiveChatMembershipItemRenderer {
     #[serde(flatten)]
     pub message_renderer_base: MessageRendererBase,
+    #[serde(rename = "headerPrimaryText")]
+    pub header_primary_text: Option<Message>,
     #[serde(rename = "headerSubText")]
     pub header_sub_text: Option<HeaderSubText>,
+    pub message: Option<Message>,
     #[serde(rename = "authorBadges")]
     pub author_badges: Vec<AuthorBadge>,
+    /// Keys not modelled above, which `flatten` would otherwise drop silently.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 #[derive(Serialize, Deserialize, Debug)]
 pub struct HeaderSubText {
-    pub runs: Vec<MessageRun>,
+    pub runs: Option<Vec<MessageRun>>,
+    #[serde(rename = "simpleText")]
+    pub simple_text: Option<String>,
 }
 #[derive(Serialize, Deserialize, Debug)]
 pub struct AddChatItemAction {
@@ -315,5 +436,262 @@ pub struct ActionItem {
     #[serde(rename = "liveChatPaidStickerRenderer")]
     pub live_chat_paid_sticker_renderer: Option<LiveChatPaidStickerRenderer>,
     #[serde(rename = "liveChatViewerEngagementMessageRenderer")]
-    pub live_chat_viewer_engagement_message_renderer: Option<serde_json::Value>,
+    pub live_chat_viewer_engagement_message_renderer:
+        Option<LiveChatViewerEngagementMessageRenderer>,
+    #[serde(rename = "liveChatBannerRedirectRenderer")]
+    pub live_chat_banner_redirect_renderer: Option<LiveChatBannerRedirectRenderer>,
+    #[serde(rename = "liveChatSponsorshipsGiftPurchaseAnnouncementRenderer")]
+    pub live_chat_sponsorships_gift_purchase_announcement_renderer:
+        Option<LiveChatSponsorshipsGiftPurchaseAnnouncementRenderer>,
+    #[serde(rename = "liveChatSponsorshipsGiftRedemptionAnnouncementRenderer")]
+    pub live_chat_sponsorships_gift_redemption_announcement_renderer:
+        Option<LiveChatSponsorshipsGiftRedemptionAnnouncementRenderer>,
+}
+
+/* Gift memberships */
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LiveChatSponsorshipsGiftPurchaseAnnouncementRenderer {
+    pub id: String,
+    #[serde(rename = "timestampUsec")]
+    pub timestamp_usec: String,
+    #[serde(rename = "authorExternalChannelId")]
+    pub author_external_channel_id: String,
+    pub header: LiveChatSponsorshipsHeader,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LiveChatSponsorshipsHeader {
+    #[serde(rename = "liveChatSponsorshipsHeaderRenderer")]
+    pub live_chat_sponsorships_header_renderer: LiveChatSponsorshipsHeaderRenderer,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LiveChatSponsorshipsHeaderRenderer {
+    #[serde(rename = "authorName")]
+    pub author_name: Option<AuthorName>,
+    #[serde(rename = "authorPhoto")]
+    pub author_photo: AuthorPhoto,
+    #[serde(rename = "primaryText")]
+    pub primary_text: Message,
+    #[serde(rename = "authorBadges")]
+    pub author_badges: Option<Vec<AuthorBadge>>,
+    pub image: Option<CustomThumbnail>,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LiveChatSponsorshipsGiftRedemptionAnnouncementRenderer {
+    pub id: String,
+    #[serde(rename = "timestampUsec")]
+    pub timestamp_usec: String,
+    #[serde(rename = "authorExternalChannelId")]
+    pub author_external_channel_id: String,
+    #[serde(rename = "authorName")]
+    pub author_name: Option<AuthorName>,
+    #[serde(rename = "authorPhoto")]
+    pub author_photo: AuthorPhoto,
+    #[serde(rename = "authorBadges")]
+    pub author_badges: Option<Vec<AuthorBadge>>,
+    pub message: Message,
+}
+/* Gift memberships End */
+
+/* Ticker */
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AddLiveChatTickerItemAction {
+    pub item: TickerItem,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TickerItem {
+    #[serde(rename = "liveChatTickerPaidMessageItemRenderer")]
+    pub live_chat_ticker_paid_message_item_renderer: Option<LiveChatTickerPaidMessageItemRenderer>,
+    #[serde(rename = "liveChatTickerPaidStickerItemRenderer")]
+    pub live_chat_ticker_paid_sticker_item_renderer: Option<LiveChatTickerPaidStickerItemRenderer>,
+    #[serde(rename = "liveChatTickerSponsorItemRenderer")]
+    pub live_chat_ticker_sponsor_item_renderer: Option<LiveChatTickerSponsorItemRenderer>,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TickerRendererBase {
+    pub id: String,
+    #[serde(rename = "authorExternalChannelId")]
+    pub author_external_channel_id: String,
+    #[serde(rename = "durationSec", default)]
+    pub duration_sec: u64,
+    #[serde(rename = "fullDurationSec", default)]
+    pub full_duration_sec: u64,
+    #[serde(rename = "showItemEndpoint")]
+    pub show_item_endpoint: Option<ShowItemEndpoint>,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ShowItemEndpoint {
+    #[serde(rename = "showLiveChatItemEndpoint")]
+    pub show_live_chat_item_endpoint: ShowLiveChatItemEndpoint,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ShowLiveChatItemEndpoint {
+    pub renderer: ActionItem,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LiveChatTickerPaidMessageItemRenderer {
+    #[serde(flatten)]
+    pub ticker_renderer_base: TickerRendererBase,
+    #[serde(rename = "authorPhoto")]
+    pub author_photo: AuthorPhoto,
+    pub amount: Option<PurchaseAmountText>,
+    /// Keys not modelled above, which `flatten` would otherwise drop silently.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LiveChatTickerPaidStickerItemRenderer {
+    #[serde(flatten)]
+    pub ticker_renderer_base: TickerRendererBase,
+    #[serde(rename = "authorPhoto")]
+    pub author_photo: AuthorPhoto,
+    #[serde(rename = "tickerThumbnails")]
+    pub ticker_thumbnails: Option<Vec<Sticker>>,
+    /// Keys not modelled above, which `flatten` would otherwise drop silently.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LiveChatTickerSponsorItemRenderer {
+    #[serde(flatten)]
+    pub ticker_renderer_base: TickerRendererBase,
+    #[serde(rename = "sponsorPhoto")]
+    pub sponsor_photo: AuthorPhoto,
+    #[serde(rename = "detailText")]
+    pub detail_text: Option<HeaderSubText>,
+    /// Keys not modelled above, which `flatten` would otherwise drop silently.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+/* Ticker End */
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LiveChatViewerEngagementMessageRenderer {
+    #[serde(default)]
+    pub id: String,
+    #[serde(rename = "timestampUsec")]
+    pub timestamp_usec: Option<String>,
+    pub icon: Option<Icon>,
+    pub message: Option<Message>,
+}
+
+/* Banner */
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AddBannerToLiveChatCommand {
+    #[serde(rename = "bannerRenderer")]
+    pub banner_renderer: BannerRenderer,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BannerRenderer {
+    #[serde(rename = "liveChatBannerRenderer")]
+    pub live_chat_banner_renderer: LiveChatBannerRenderer,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LiveChatBannerRenderer {
+    pub header: Option<LiveChatBannerHeader>,
+    pub contents: Option<ActionItem>,
+    #[serde(rename = "actionId", default)]
+    pub action_id: String,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LiveChatBannerHeader {
+    #[serde(rename = "liveChatBannerHeaderRenderer")]
+    pub live_chat_banner_header_renderer: LiveChatBannerHeaderRenderer,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LiveChatBannerHeaderRenderer {
+    pub text: Option<HeaderSubText>,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RemoveBannerForLiveChatCommand {
+    #[serde(rename = "targetActionId", default)]
+    pub target_action_id: String,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LiveChatBannerRedirectRenderer {
+    #[serde(rename = "bannerMessage")]
+    pub banner_message: Message,
+    #[serde(rename = "inlineActionButton")]
+    pub inline_action_button: Option<InlineActionButton>,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct InlineActionButton {
+    #[serde(rename = "buttonRenderer")]
+    pub button_renderer: ButtonRenderer,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ButtonRenderer {
+    pub command: Option<ButtonCommand>,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ButtonCommand {
+    #[serde(rename = "watchEndpoint")]
+    pub watch_endpoint: Option<WatchEndpoint>,
+}
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WatchEndpoint {
+    #[serde(rename = "videoId")]
+    pub video_id: String,
+}
+/* Banner End */
+
+/* Poll */
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ShowLiveChatActionPanelAction {
+    #[serde(rename = "panelToShow")]
+    pub panel_to_show: PanelToShow,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PanelToShow {
+    #[serde(rename = "liveChatActionPanelRenderer")]
+    pub live_chat_action_panel_renderer: LiveChatActionPanelRenderer,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LiveChatActionPanelRenderer {
+    #[serde(default)]
+    pub id: String,
+    pub contents: Option<ActionPanelContents>,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ActionPanelContents {
+    #[serde(rename = "pollRenderer")]
+    pub poll_renderer: Option<PollRenderer>,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateLiveChatPollAction {
+    #[serde(rename = "pollToUpdate")]
+    pub poll_to_update: ActionPanelContents,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CloseLiveChatActionPanelAction {
+    #[serde(rename = "targetPanelId", default)]
+    pub target_panel_id: String,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PollRenderer {
+    #[serde(rename = "liveChatPollId", default)]
+    pub live_chat_poll_id: String,
+    pub header: Option<PollHeader>,
+    #[serde(default)]
+    pub choices: Vec<PollChoice>,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PollHeader {
+    #[serde(rename = "pollHeaderRenderer")]
+    pub poll_header_renderer: PollHeaderRenderer,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PollHeaderRenderer {
+    #[serde(rename = "pollQuestion")]
+    pub poll_question: Option<HeaderSubText>,
+    #[serde(rename = "metadataText")]
+    pub metadata_text: Option<HeaderSubText>,
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PollChoice {
+    pub text: Option<HeaderSubText>,
+    #[serde(rename = "voteRatio")]
+    pub vote_ratio: Option<f64>,
+    #[serde(rename = "votePercentage")]
+    pub vote_percentage: Option<HeaderSubText>,
+}
+/* Poll End */
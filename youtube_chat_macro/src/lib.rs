@@ -1,148 +1,402 @@
-use quote::quote;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+    Data, DeriveInput, Fields, GenericParam, Ident, Token, Type, TypeParamBound, Visibility,
+    WherePredicate,
+};
 
-static TYPE_VARS: [&str; 4] = ["SF", "ENF", "CF", "ERF"];
-static FN_FIELD_NAMES: [&str; 4] = ["on_start", "on_end", "on_chat", "on_error"];
-static EMPTY_TYPE: &str = "Empty";
+/// Derives a typestate builder from a builder struct definition.
+///
+/// ```ignore
+/// #[derive(TypestateBuilder)]
+/// #[typestate(client = LiveChatClient, empty = Empty)]
+/// pub struct LiveChatClientBuilder<U, SF, CF>
+/// where
+///     SF: InvokeOnStart,
+///     CF: InvokeOnChat,
+/// {
+///     #[typestate(ready = String)]
+///     live_url: U,
+///     #[handler(live_id: String)]
+///     on_start: SF,
+///     #[handler(chat_item: ChatItem)]
+///     on_chat: CF,
+///     state: ClientState,
+/// }
+/// ```
+///
+/// For every `#[handler(..)]` field, whose type is a type parameter bounded by
+/// a trait in the where clause, this generates
+/// - the trait with a no-op `invoke_<field>` method, implemented for the empty
+///   type, for `Fn(..)` closures and for the client (delegating to its field),
+/// - a builder method `<field>` setting the handler while it is still empty.
+///
+/// It also generates `build`, available once the `ready` field has its ready
+/// type, which moves every field into the client struct of the same field
+/// names, and a private `with_<field>` method replacing the `ready` field.
+#[proc_macro_derive(TypestateBuilder, attributes(typestate, handler))]
+pub fn derive_typestate_builder(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(tokens as DeriveInput);
+    typestate_builder_impl(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+struct TypestateArg {
+    key: Ident,
+    value: Type,
+}
+
+impl Parse for TypestateArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let value = input.parse()?;
+        Ok(Self { key, value })
+    }
+}
+
+struct HandlerArg {
+    name: Ident,
+    ty: Type,
+}
+
+impl Parse for HandlerArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let ty = input.parse()?;
+        Ok(Self { name, ty })
+    }
+}
+
+struct Handler {
+    field: Ident,
+    type_var: Ident,
+    invoke_trait: TokenStream,
+    args: Vec<HandlerArg>,
+}
 
-#[proc_macro]
-pub fn gen_builder(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    gen_builder_impl(tokens.into()).into()
+struct Ready {
+    field: Ident,
+    type_var: Ident,
+    ready_type: Type,
 }
 
-fn gen_builder_impl(_tokens: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
-    let types: Vec<proc_macro2::TokenStream> = TYPE_VARS
-        .into_iter()
-        .map(|name| name.parse().unwrap())
+fn typestate_args(attrs: &[syn::Attribute]) -> syn::Result<Vec<TypestateArg>> {
+    let mut args = Vec::new();
+    for attr in attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("typestate"))
+    {
+        args.extend(attr.parse_args_with(Punctuated::<TypestateArg, Token![,]>::parse_terminated)?);
+    }
+    Ok(args)
+}
+
+fn type_var_of(ty: &Type) -> Option<Ident> {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.get_ident().cloned(),
+        _ => None,
+    }
+}
+
+fn predicate_type_var(predicate: &WherePredicate) -> Option<Ident> {
+    match predicate {
+        WherePredicate::Type(predicate) => type_var_of(&predicate.bounded_ty),
+        _ => None,
+    }
+}
+
+fn typestate_builder_impl(input: DeriveInput) -> syn::Result<TokenStream> {
+    let builder = &input.ident;
+    let vis: &Visibility = &input.vis;
+    let mut client = None;
+    let mut empty = None;
+    for arg in typestate_args(&input.attrs)? {
+        match arg.key.to_string().as_str() {
+            "client" => client = Some(arg.value),
+            "empty" => empty = Some(arg.value),
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    arg.key,
+                    "expected `client` or `empty`",
+                ))
+            }
+        }
+    }
+    let client = client
+        .ok_or_else(|| syn::Error::new_spanned(builder, "missing `#[typestate(client = ..)]`"))?;
+    let empty = empty
+        .ok_or_else(|| syn::Error::new_spanned(builder, "missing `#[typestate(empty = ..)]`"))?;
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(syn::Error::new_spanned(builder, "expected named fields")),
+        },
+        _ => return Err(syn::Error::new_spanned(builder, "expected a struct")),
+    };
+
+    let type_vars: Vec<Ident> = input
+        .generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Type(param) => Ok(param.ident.clone()),
+            _ => Err(syn::Error::new_spanned(
+                param,
+                "expected type parameters only",
+            )),
+        })
+        .collect::<syn::Result<_>>()?;
+    let predicates: Vec<WherePredicate> = input
+        .generics
+        .where_clause
+        .iter()
+        .flat_map(|where_clause| where_clause.predicates.iter().cloned())
         .collect();
-    let field_values: Vec<proc_macro2::TokenStream> = FN_FIELD_NAMES
-        .into_iter()
-        .map(|name| name.parse().unwrap())
-        .map(|token: proc_macro2::TokenStream| quote!(self.#token))
+
+    let mut handlers = Vec::new();
+    let mut ready = None;
+    for field in fields {
+        let field_ident = field.ident.clone().unwrap();
+        let type_var = type_var_of(&field.ty).filter(|ty| type_vars.contains(ty));
+        if let Some(attr) = field
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("handler"))
+        {
+            let type_var = type_var.ok_or_else(|| {
+                syn::Error::new_spanned(&field.ty, "handler must be a type parameter")
+            })?;
+            let invoke_trait = predicates
+                .iter()
+                .filter(|predicate| predicate_type_var(predicate).as_ref() == Some(&type_var))
+                .find_map(|predicate| match predicate {
+                    WherePredicate::Type(predicate) => {
+                        predicate.bounds.iter().find_map(|bound| match bound {
+                            TypeParamBound::Trait(bound) => Some(quote!(#bound)),
+                            _ => None,
+                        })
+                    }
+                    _ => None,
+                })
+                .ok_or_else(|| {
+                    syn::Error::new_spanned(
+                        &field.ty,
+                        "handler must be bounded by a trait in the where clause",
+                    )
+                })?;
+            let args = attr
+                .parse_args_with(Punctuated::<HandlerArg, Token![,]>::parse_terminated)?
+                .into_iter()
+                .collect();
+            handlers.push(Handler {
+                field: field_ident,
+                type_var,
+                invoke_trait,
+                args,
+            });
+        } else if let Some(arg) = typestate_args(&field.attrs)?.into_iter().next() {
+            if arg.key != "ready" {
+                return Err(syn::Error::new_spanned(arg.key, "expected `ready`"));
+            }
+            let type_var = type_var.ok_or_else(|| {
+                syn::Error::new_spanned(&field.ty, "ready field must be a type parameter")
+            })?;
+            ready = Some(Ready {
+                field: field_ident,
+                type_var,
+                ready_type: arg.value,
+            });
+        }
+    }
+    let ready = ready.ok_or_else(|| {
+        syn::Error::new_spanned(builder, "missing a `#[typestate(ready = ..)]` field")
+    })?;
+
+    let field_idents: Vec<&Ident> = fields
+        .iter()
+        .map(|field| field.ident.as_ref().unwrap())
+        .collect();
+    let client_type_vars: Vec<&Ident> = handlers.iter().map(|handler| &handler.type_var).collect();
+    let client_predicates: Vec<&WherePredicate> = predicates
+        .iter()
+        .filter(|predicate| {
+            predicate_type_var(predicate).is_some_and(|ty| client_type_vars.contains(&&ty))
+        })
         .collect();
-    let empty_type: proc_macro2::TokenStream = EMPTY_TYPE.parse().unwrap();
-    let n_types = types.len();
-    let mut tokens = proc_macro2::TokenStream::new();
-    for bit in 0..(1 << n_types) {
-        let flags: Vec<bool> = (0..n_types).map(|x| (bit & (1 << x)) != 0).collect();
-        let types: Vec<(
-            proc_macro2::TokenStream,
-            proc_macro2::TokenStream,
-            proc_macro2::TokenStream,
-            bool, // flag
-        )> = flags
+    let predicates_except = |type_var: &Ident| -> Vec<&WherePredicate> {
+        predicates
+            .iter()
+            .filter(|predicate| predicate_type_var(predicate).as_ref() != Some(type_var))
+            .collect()
+    };
+    let type_vars_except = |type_var: &Ident| -> Vec<&Ident> {
+        type_vars
             .iter()
-            .enumerate()
-            .map(|(idx, &flag)| {
-                if flag {
-                    let ty = types[idx].clone();
-                    let where_clause = type_var_to_where_constraint(&ty);
-                    (ty, field_values[idx].clone(), where_clause, flag)
+            .filter(|other| *other != type_var)
+            .collect()
+    };
+    let type_args_replacing = |type_var: &Ident, with: TokenStream| -> Vec<TokenStream> {
+        type_vars
+            .iter()
+            .map(|other| {
+                if other == type_var {
+                    with.clone()
                 } else {
-                    (
-                        empty_type.clone(),
-                        empty_type.clone(),
-                        proc_macro2::TokenStream::new(),
-                        flag,
-                    )
+                    quote!(#other)
                 }
             })
-            .collect();
-        let type_vars: proc_macro2::TokenStream = flags
+            .collect()
+    };
+    let field_values_replacing = |field: &Ident, with: TokenStream| -> Vec<TokenStream> {
+        field_idents
             .iter()
-            .enumerate()
-            .filter_map(|(idx, &flag)| {
-                if flag {
-                    Some(types[idx].0.to_string())
+            .map(|other| {
+                if *other == field {
+                    with.clone()
                 } else {
-                    None
+                    quote!(self.#other)
                 }
             })
-            .collect::<Vec<String>>()
-            .join(", ")
-            .parse()
-            .unwrap();
-        let is_all_empty_type = types
-            .iter()
-            .map(|(_, _, _, flag)| flag)
-            .all(|is_not_empty| !is_not_empty);
-        #[allow(non_snake_case)]
-        let (SF, ENF, CF, ERF) = (
-            types[0].0.clone(),
-            types[1].0.clone(),
-            types[2].0.clone(),
-            types[3].0.clone(),
-        );
-        let (on_start, on_end, on_chat, on_error) = (
-            types[0].1.clone(),
-            types[1].1.clone(),
-            types[2].1.clone(),
-            types[3].1.clone(),
-        );
-        let where_clauses: proc_macro2::TokenStream = flags
+            .collect()
+    };
+
+    let mut tokens = TokenStream::new();
+    for handler in &handlers {
+        let Handler {
+            field,
+            type_var,
+            invoke_trait,
+            args,
+        } = handler;
+        let invoke_fn = format_ident!("invoke_{}", field);
+        let arg_names: Vec<&Ident> = args.iter().map(|arg| &arg.name).collect();
+        let unused_arg_names: Vec<Ident> = arg_names
             .iter()
-            .enumerate()
-            .filter_map(|(idx, &flag)| {
-                if flag {
-                    Some(types[idx].2.to_string())
-                } else {
-                    None
+            .map(|name| format_ident!("_{}", name))
+            .collect();
+        let arg_types: Vec<&Type> = args.iter().map(|arg| &arg.ty).collect();
+        tokens.extend(quote!(
+            #vis trait #invoke_trait {
+                /// Whether a handler was given, so work only the handler needs can be skipped.
+                const IS_SET: bool = false;
+
+                fn #invoke_fn(&self, #(#unused_arg_names: #arg_types),*) {}
+            }
+            impl #invoke_trait for #empty {}
+            impl<T> #invoke_trait for T
+            where
+                T: Fn(#(#arg_types),*),
+            {
+                const IS_SET: bool = true;
+
+                fn #invoke_fn(&self, #(#arg_names: #arg_types),*) {
+                    (self)(#(#arg_names),*)
                 }
-            })
-            .collect::<Vec<String>>()
-            .join(", ")
-            .parse()
-            .unwrap();
-        let build_impl = if is_all_empty_type {
-            quote!(
-                impl LiveChatClientBuilder<String, #SF, #ENF, #CF, #ERF> {
-                    pub fn build(self) -> LiveChatClient<#SF, #ENF, #CF, #ERF> {
-                        LiveChatClient {
-                            live_url: self.live_url,
-                            on_start: #on_start,
-                            on_end: #on_end,
-                            on_chat: #on_chat,
-                            on_error: #on_error,
-                            options: None,
-                        }
-                    }
+            }
+            impl<#(#client_type_vars),*> #invoke_trait for #client<#(#client_type_vars),*>
+            where
+                #(#client_predicates),*
+            {
+                const IS_SET: bool = <#type_var as #invoke_trait>::IS_SET;
+
+                fn #invoke_fn(&self, #(#arg_names: #arg_types),*) {
+                    self.#field.#invoke_fn(#(#arg_names),*)
                 }
-            )
-        } else {
-            quote!(
-                impl<#type_vars> LiveChatClientBuilder<String, #SF, #ENF, #CF, #ERF>
+            }
+        ));
+
+        let impl_type_vars = type_vars_except(type_var);
+        let impl_predicates = predicates_except(type_var);
+        let empty_type_args = type_args_replacing(type_var, quote!(#empty));
+        let field_values = field_values_replacing(field, quote!(f));
+        tokens.extend(quote!(
+            impl<#(#impl_type_vars),*> #builder<#(#empty_type_args),*>
+            where
+                #(#impl_predicates),*
+            {
+                pub fn #field<#type_var>(self, f: #type_var) -> #builder<#(#type_vars),*>
                 where
-                    #where_clauses
+                    #type_var: Fn(#(#arg_types),*),
                 {
-                    pub fn build(self) -> LiveChatClient<#SF, #ENF, #CF, #ERF> {
-                        LiveChatClient {
-                            live_url: self.live_url,
-                            on_start: #on_start,
-                            on_end: #on_end,
-                            on_chat: #on_chat,
-                            on_error: #on_error,
-                            options: None,
-                        }
+                    #builder {
+                        #(#field_idents: #field_values,)*
                     }
                 }
-            )
-        };
-        tokens.extend(build_impl);
+            }
+        ));
     }
-    tokens
-}
 
-fn type_var_to_where_constraint(type_var: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
-    match type_var.to_string().as_str() {
-        "SF" => quote!(#type_var: Fn(String)),
-        "ENF" => quote!(#type_var: Fn()),
-        "CF" => quote!(#type_var: Fn(ChatItem)),
-        "ERF" => quote!(#type_var: Fn(anyhow::Error)),
-        t => unreachable!("unexpected type var: {}", t),
-    }
+    let Ready {
+        field: ready_field,
+        type_var: ready_type_var,
+        ready_type,
+    } = &ready;
+    let impl_type_vars = type_vars_except(ready_type_var);
+    let impl_predicates = predicates_except(ready_type_var);
+    let ready_type_args = type_args_replacing(ready_type_var, quote!(#ready_type));
+    tokens.extend(quote!(
+        impl<#(#impl_type_vars),*> #builder<#(#ready_type_args),*>
+        where
+            #(#impl_predicates),*
+        {
+            pub fn build(self) -> #client<#(#client_type_vars),*> {
+                #client {
+                    #(#field_idents: self.#field_idents,)*
+                }
+            }
+        }
+    ));
+
+    let with_ready_field = format_ident!("with_{}", ready_field);
+    let new_type_var = format_ident!("New{}", ready_type_var);
+    let new_type_args = type_args_replacing(ready_type_var, quote!(#new_type_var));
+    let field_values = field_values_replacing(ready_field, quote!(#ready_field));
+    tokens.extend(quote!(
+        impl<#(#type_vars),*> #builder<#(#type_vars),*>
+        where
+            #(#predicates),*
+        {
+            fn #with_ready_field<#new_type_var>(
+                self,
+                #ready_field: #new_type_var,
+            ) -> #builder<#(#new_type_args),*> {
+                #builder {
+                    #(#field_idents: #field_values,)*
+                }
+            }
+        }
+    ));
+    Ok(tokens)
 }
 
 #[test]
 fn snapshot_impl() {
-    let expanded = gen_builder_impl(quote!());
+    let input = syn::parse_quote!(
+        #[typestate(client = LiveChatClient, empty = Empty)]
+        pub struct LiveChatClientBuilder<U, SF, ENF, CF>
+        where
+            SF: InvokeOnStart,
+            ENF: InvokeOnEnd,
+            CF: InvokeOnChat,
+        {
+            #[typestate(ready = String)]
+            live_url: U,
+            #[handler(live_id: String)]
+            on_start: SF,
+            #[handler()]
+            on_end: ENF,
+            #[handler(chat_item: ChatItem)]
+            on_chat: CF,
+            state: ClientState,
+        }
+    );
+    let expanded = typestate_builder_impl(input).unwrap();
     insta::assert_display_snapshot!(expanded.to_string());
 }
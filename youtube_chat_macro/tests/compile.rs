@@ -0,0 +1,7 @@
+#[test]
+fn typestate_builder() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/ui/typestate_builder.rs");
+    cases.compile_fail("tests/ui/handler_set_twice.rs");
+    cases.compile_fail("tests/ui/build_before_ready.rs");
+}
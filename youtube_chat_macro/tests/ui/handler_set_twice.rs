@@ -0,0 +1,30 @@
+use youtube_chat_macro::TypestateBuilder;
+
+pub struct Empty;
+
+pub struct Client<CF: InvokeOnChat> {
+    url: String,
+    on_chat: CF,
+}
+
+#[derive(TypestateBuilder)]
+#[typestate(client = Client, empty = Empty)]
+pub struct ClientBuilder<U, CF>
+where
+    CF: InvokeOnChat,
+{
+    #[typestate(ready = String)]
+    url: U,
+    #[handler(message: String)]
+    on_chat: CF,
+}
+
+fn ignore(_message: String) {}
+
+fn main() {
+    let builder = ClientBuilder {
+        url: String::new(),
+        on_chat: Empty,
+    };
+    let _ = builder.on_chat(ignore).on_chat(ignore);
+}
@@ -0,0 +1,72 @@
+use std::cell::Cell;
+
+use youtube_chat_macro::TypestateBuilder;
+
+pub struct Empty;
+
+pub struct Client<SF, CF>
+where
+    SF: InvokeOnStart,
+    CF: InvokeOnChat,
+{
+    url: String,
+    on_start: SF,
+    on_chat: CF,
+    count: u32,
+}
+
+#[derive(TypestateBuilder)]
+#[typestate(client = Client, empty = Empty)]
+pub struct ClientBuilder<U, SF, CF>
+where
+    SF: InvokeOnStart,
+    CF: InvokeOnChat,
+{
+    #[typestate(ready = String)]
+    url: U,
+    #[handler(live_id: String)]
+    on_start: SF,
+    #[handler(message: String, count: u32)]
+    on_chat: CF,
+    count: u32,
+}
+
+impl ClientBuilder<(), Empty, Empty> {
+    fn new() -> Self {
+        ClientBuilder {
+            url: (),
+            on_start: Empty,
+            on_chat: Empty,
+            count: 0,
+        }
+    }
+}
+
+impl<SF: InvokeOnStart, CF: InvokeOnChat> ClientBuilder<(), SF, CF> {
+    fn url(self, url: &str) -> ClientBuilder<String, SF, CF> {
+        self.with_url(url.to_string())
+    }
+}
+
+fn main() {
+    let chats = Cell::new(0);
+    let client = ClientBuilder::new()
+        .on_chat(|_message: String, count: u32| chats.set(chats.get() + count))
+        .url("https://www.youtube.com/watch?v=jfKfPfyJRdk")
+        .build();
+    assert_eq!(client.url, "https://www.youtube.com/watch?v=jfKfPfyJRdk");
+    assert_eq!(client.count, 0);
+
+    fn is_set<T: InvokeOnChat>(_: &T) -> bool {
+        T::IS_SET
+    }
+    assert!(is_set(&client));
+    assert!(!<Empty as InvokeOnStart>::IS_SET);
+    assert!(!<Client<Empty, Empty> as InvokeOnChat>::IS_SET);
+
+    client.invoke_on_start("jfKfPfyJRdk".to_string());
+    client.invoke_on_chat("hello".to_string(), 2);
+    client.on_chat.invoke_on_chat("again".to_string(), 3);
+    assert_eq!(chats.get(), 5);
+    let _ = &client.on_start;
+}
@@ -0,0 +1,158 @@
+//! `wasm-bindgen` bindings for browser overlays: a chat client that passes every
+//! chat event to JavaScript as a plain object.
+
+use std::{cell::RefCell, fmt::Display, rc::Rc, time::Duration};
+
+use js_sys::{Array, Function, Promise};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::future_to_promise;
+
+use youtube_chat::{
+    item::ChatEvent,
+    live_chat::{Empty, LiveChatClient, LiveChatClientBuilder},
+    parser::ParseOptions,
+    request::parse_get_live_chat,
+};
+
+type Handler<T> = Box<dyn Fn(T)>;
+
+type Client = LiveChatClient<
+    Empty,
+    Empty,
+    Empty,
+    Handler<anyhow::Error>,
+    Empty,
+    Empty,
+    Empty,
+    Empty,
+    Empty,
+    Empty,
+    Empty,
+    Empty,
+    Empty,
+    Empty,
+    Empty,
+    Empty,
+    Handler<ChatEvent>,
+>;
+
+/// A live chat client for JavaScript. `start` and `execute` return promises;
+/// while one of them is pending, the other methods throw.
+#[wasm_bindgen]
+pub struct ChatClient {
+    client: Rc<RefCell<Option<Client>>>,
+}
+
+#[wasm_bindgen]
+impl ChatClient {
+    /// `on_event` is called with every chat event as an object such as
+    /// `{ "Chat": { ... } }`, `on_error` with an error message.
+    #[wasm_bindgen(constructor)]
+    pub fn new(url: String, on_event: Function, on_error: Function) -> Result<ChatClient, JsValue> {
+        let on_error = Rc::new(on_error);
+        let on_event: Handler<ChatEvent> = {
+            let on_error = on_error.clone();
+            Box::new(move |chat_event| match to_js(&chat_event) {
+                Ok(value) => {
+                    let _ = on_event.call1(&JsValue::NULL, &value);
+                }
+                Err(err) => {
+                    let _ = on_error.call1(&JsValue::NULL, &err);
+                }
+            })
+        };
+        let on_error: Handler<anyhow::Error> = Box::new(move |err| {
+            let _ = on_error.call1(&JsValue::NULL, &JsValue::from_str(&err.to_string()));
+        });
+        let client = LiveChatClientBuilder::new()
+            .url(url)
+            .map_err(js_error)?
+            .on_event(on_event)
+            .on_error(on_error)
+            .build();
+        Ok(ChatClient {
+            client: Rc::new(RefCell::new(Some(client))),
+        })
+    }
+
+    /// Reads the live page; resolves when the client is ready to `execute`.
+    pub fn start(&self) -> Promise {
+        let cell = self.client.clone();
+        future_to_promise(async move {
+            let mut client = take(&cell)?;
+            let result = client.start().await;
+            cell.replace(Some(client));
+            result.map(|_| JsValue::UNDEFINED).map_err(js_error)
+        })
+    }
+
+    /// Fetches and delivers the next chat events; errors go to `on_error`.
+    pub fn execute(&self) -> Promise {
+        let cell = self.client.clone();
+        future_to_promise(async move {
+            let mut client = take(&cell)?;
+            client.execute().await;
+            cell.replace(Some(client));
+            Ok(JsValue::UNDEFINED)
+        })
+    }
+
+    /// Stops the client; `execute` reports an error until it is started again.
+    pub fn stop(&self) -> Promise {
+        let cell = self.client.clone();
+        future_to_promise(async move {
+            let mut client = take(&cell)?;
+            let result = client.stop().await;
+            cell.replace(Some(client));
+            result.map(|_| JsValue::UNDEFINED).map_err(js_error)
+        })
+    }
+
+    /// Whether the last response had no continuation, e.g. because the stream ended.
+    #[wasm_bindgen(js_name = hasEnded)]
+    pub fn has_ended(&self) -> Result<bool, JsValue> {
+        self.with_client(|client| client.has_ended())
+    }
+
+    /// Milliseconds to wait before the next `execute`, `interval_ms` at most.
+    #[wasm_bindgen(js_name = pollDelay)]
+    pub fn poll_delay(&self, interval_ms: f64) -> Result<f64, JsValue> {
+        let interval = Duration::from_secs_f64(interval_ms.max(0.0) / 1000.0);
+        self.with_client(|client| client.poll_delay(interval).as_secs_f64() * 1000.0)
+    }
+
+    fn with_client<T>(&self, f: impl FnOnce(&mut Client) -> T) -> Result<T, JsValue> {
+        match self.client.borrow_mut().as_mut() {
+            Some(client) => Ok(f(client)),
+            None => Err(busy()),
+        }
+    }
+}
+
+/// Parses a `get_live_chat` response body fetched by JavaScript, e.g. through a
+/// proxy, into chat event objects.
+#[wasm_bindgen(js_name = parseChatEvents)]
+pub fn parse_chat_events(body: &[u8]) -> Result<Array, JsValue> {
+    let (chat_events, _, _) =
+        parse_get_live_chat(body, &ParseOptions::default(), None).map_err(js_error)?;
+    chat_events.iter().map(to_js).collect()
+}
+
+fn take(cell: &RefCell<Option<Client>>) -> Result<Client, JsValue> {
+    cell.borrow_mut().take().ok_or_else(busy)
+}
+
+fn to_js(value: &impl Serialize) -> Result<JsValue, JsValue> {
+    value
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(Into::into)
+}
+
+fn busy() -> JsValue {
+    js_error("the client is busy with `start` or `execute`")
+}
+
+fn js_error(err: impl Display) -> JsValue {
+    JsError::new(&err.to_string()).into()
+}